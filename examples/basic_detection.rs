@@ -3,7 +3,6 @@
 use ocr_credential_detector::{CredentialDetector, OcrEngine};
 use std::env;
 use std::fs;
-use std::path::Path;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Get image file path from command line arguments
@@ -98,6 +97,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             );
             println!("  Credential score: {:.2}", detection.credential_score);
             println!("  Detector: {}", detection.detector_name);
+            println!(
+                "  Rule: {} ({:?}, {:?})",
+                detection.rule_id, detection.severity, detection.category
+            );
         }
     }
 
@@ -1,10 +1,10 @@
 //! Example of batch processing multiple images
 
-use ocr_credential_detector::{CredentialDetection, CredentialDetector};
+use ocr_credential_detector::{BatchScanner, CredentialDetection, CredentialDetector};
 use std::collections::HashMap;
 use std::env;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Get image directory path from command line arguments
@@ -28,22 +28,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("Number of image files to process: {}", image_paths.len());
 
-    // Initialize CredentialDetector
-    let detector = CredentialDetector::new()?;
+    // Initialize CredentialDetector, shared by the worker threads of the batch scanner
+    let scanner = BatchScanner::new(CredentialDetector::new()?);
 
-    // Process each image
+    // Process the images in parallel, as they complete
     let mut all_detections: HashMap<PathBuf, Vec<CredentialDetection>> = HashMap::new();
     let mut total_detections = 0;
 
-    for path in &image_paths {
-        println!("Processing: {}", path.display());
+    for result in scanner.scan(image_paths.clone()) {
+        println!("Processed: {}", result.name);
 
-        match detector.detect_from_path(path) {
+        match result.result {
             Ok(detections) => {
                 if !detections.is_empty() {
                     println!("  Number of detected credentials: {}", detections.len());
                     total_detections += detections.len();
-                    all_detections.insert(path.clone(), detections);
+                    all_detections.insert(image_paths[result.index].clone(), detections);
                 } else {
                     println!("  No credentials were detected.");
                 }
@@ -92,14 +92,14 @@ fn get_image_files(dir_path: &str) -> Result<Vec<PathBuf>, Box<dyn std::error::E
         let entry = entry?;
         let path = entry.path();
 
-        if path.is_file() {
-            if let Some(extension) = path.extension() {
-                let ext = extension.to_string_lossy().to_lowercase();
+        if path.is_file()
+            && let Some(extension) = path.extension()
+        {
+            let ext = extension.to_string_lossy().to_lowercase();
 
-                // Common image file formats
-                if ["jpg", "jpeg", "png", "gif", "bmp", "tiff", "webp"].contains(&ext.as_str()) {
-                    image_paths.push(path);
-                }
+            // Common image file formats
+            if ["jpg", "jpeg", "png", "gif", "bmp", "tiff", "webp"].contains(&ext.as_str()) {
+                image_paths.push(path);
             }
         }
     }
@@ -5,24 +5,26 @@ use ocr_credential_detector::{
 };
 use regex::Regex;
 use std::env;
-use std::path::Path;
 
-/// Credit card number detector
-struct CreditCardDetector {
+/// Slack incoming webhook URL detector
+struct SlackWebhookDetector {
     pattern: Regex,
 }
 
-impl CreditCardDetector {
+impl SlackWebhookDetector {
     fn new() -> Self {
-        // Credit card number pattern (16 digits, possibly separated by spaces or hyphens every 4 digits)
-        let pattern = Regex::new(r"(?:\d{4}[-\s]?){3}\d{4}").unwrap();
-        CreditCardDetector { pattern }
+        // Slack incoming webhook URL pattern (workspace, channel and secret path segments)
+        let pattern = Regex::new(
+            r"https://hooks\.slack\.com/services/T[A-Z0-9]{8,}/B[A-Z0-9]{8,}/[A-Za-z0-9]{24}",
+        )
+        .unwrap();
+        SlackWebhookDetector { pattern }
     }
 }
 
-impl CredentialDetectorLogic for CreditCardDetector {
+impl CredentialDetectorLogic for SlackWebhookDetector {
     fn name(&self) -> &str {
-        "Credit Card Number Detector"
+        "Slack Webhook Detector"
     }
 
     fn detect(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
@@ -40,13 +42,13 @@ impl CredentialDetectorLogic for CreditCardDetector {
                     height: line.height,
                 };
 
-                detections.push(CredentialDetection {
-                    text: matched_text,
+                detections.push(CredentialDetection::new(
+                    self,
+                    matched_text,
                     bounding_box,
-                    text_detection_score: line.score,
-                    credential_score: 0.95, // Set high score for credit card numbers
-                    detector_name: self.name().to_string(),
-                });
+                    line.score,
+                    0.95,
+                ));
             }
         }
 
@@ -70,7 +72,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let detector = CredentialDetector::new()?;
 
     // Add custom detector
-    let custom_detector = Box::new(CreditCardDetector::new());
+    let custom_detector = Box::new(SlackWebhookDetector::new());
     let detector = detector.with_detector(custom_detector);
 
     // Detect credentials from image
@@ -0,0 +1,266 @@
+//! Parallel batch scanning
+
+use crate::detector::CredentialDetector;
+use crate::error::CredentialDetectorError;
+use crate::types::CredentialDetection;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Input of a batch scan
+#[derive(Debug, Clone)]
+pub enum BatchInput {
+    /// File read from disk, dispatched by format like [`CredentialDetector::detect_from_path`]
+    Path(PathBuf),
+    /// Image data already in memory, with a name to report it by
+    Image { name: String, data: Vec<u8> },
+}
+
+impl BatchInput {
+    /// Name of the input (the path, for files)
+    pub fn name(&self) -> String {
+        match self {
+            BatchInput::Path(path) => path.display().to_string(),
+            BatchInput::Image { name, .. } => name.clone(),
+        }
+    }
+}
+
+impl From<PathBuf> for BatchInput {
+    fn from(path: PathBuf) -> Self {
+        BatchInput::Path(path)
+    }
+}
+
+impl From<&Path> for BatchInput {
+    fn from(path: &Path) -> Self {
+        BatchInput::Path(path.to_path_buf())
+    }
+}
+
+/// Result of scanning one input of a batch
+#[derive(Debug)]
+pub struct BatchResult {
+    /// Position of the input in the batch, starting at 0
+    pub index: usize,
+    /// Name of the input
+    pub name: String,
+    /// Detections, or the error that stopped the scan of this input
+    pub result: Result<Vec<CredentialDetection>, CredentialDetectorError>,
+}
+
+/// Scans many inputs in parallel with one shared detector
+///
+/// The detector (and its loaded models) is shared by all worker threads. Results are
+/// streamed in the order inputs finish, not the order they were given; an input that
+/// fails does not abort the rest of the batch.
+pub struct BatchScanner {
+    detector: Arc<CredentialDetector>,
+    /// Number of worker threads
+    threads: usize,
+}
+
+impl BatchScanner {
+    /// Create a new batch scanner with one worker thread per available CPU
+    pub fn new(detector: CredentialDetector) -> Self {
+        Self::from_shared(Arc::new(detector))
+    }
+
+    /// Create a new batch scanner sharing a detector that is also used elsewhere
+    pub fn from_shared(detector: Arc<CredentialDetector>) -> Self {
+        let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+        Self { detector, threads }
+    }
+
+    /// Set the number of worker threads
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Start scanning the inputs, returning an iterator over the results as they complete
+    ///
+    /// Inputs are pulled from `inputs` only as workers become free, and workers wait while
+    /// results are not consumed, so a large batch is never held in memory at once.
+    /// Dropping the iterator stops the workers once their current inputs are done.
+    pub fn scan<I>(&self, inputs: I) -> BatchResults
+    where
+        I: IntoIterator,
+        I::IntoIter: Send + 'static,
+        I::Item: Into<BatchInput>,
+    {
+        let inputs = inputs.into_iter();
+        let threads = inputs
+            .size_hint()
+            .1
+            .map_or(self.threads, |len| self.threads.min(len));
+        let inputs = Arc::new(Mutex::new(inputs.enumerate()));
+        // One buffered result per worker keeps the workers busy without running ahead
+        let (sender, receiver) = mpsc::sync_channel(threads);
+
+        for _ in 0..threads {
+            let detector = Arc::clone(&self.detector);
+            let inputs = Arc::clone(&inputs);
+            let sender = sender.clone();
+
+            thread::spawn(move || {
+                loop {
+                    // A poisoned iterator only means another worker panicked while pulling
+                    let next = inputs
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .next();
+                    let Some((index, input)) = next else {
+                        break;
+                    };
+                    let input: BatchInput = input.into();
+
+                    let result = BatchResult {
+                        index,
+                        name: input.name(),
+                        result: scan_input(&detector, input),
+                    };
+                    // The receiver was dropped: nobody wants the remaining results
+                    if sender.send(result).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        BatchResults { receiver }
+    }
+}
+
+/// Scan one input, turning a panic into an error for that input
+fn scan_input(
+    detector: &CredentialDetector,
+    input: BatchInput,
+) -> Result<Vec<CredentialDetection>, CredentialDetectorError> {
+    let scan = AssertUnwindSafe(|| match &input {
+        BatchInput::Path(path) => detector.detect_from_path(path),
+        BatchInput::Image { data, .. } => detector.detect_from_image(data),
+    });
+
+    panic::catch_unwind(scan).unwrap_or_else(|_| {
+        Err(CredentialDetectorError::UnknownError(format!(
+            "Scanning {} panicked",
+            input.name()
+        )))
+    })
+}
+
+/// Results of a batch scan, in the order they complete
+pub struct BatchResults {
+    receiver: Receiver<BatchResult>,
+}
+
+impl Iterator for BatchResults {
+    type Item = BatchResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr::TextLine;
+    use image::{ImageFormat, RgbImage};
+    use std::io::Cursor;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn png() -> Vec<u8> {
+        let mut data = Vec::new();
+        RgbImage::new(8, 8)
+            .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+            .unwrap();
+        data
+    }
+
+    fn scanner(threads: usize) -> BatchScanner {
+        let detector = CredentialDetector::from_text_extractor(|image_data: &[u8]| {
+            image::load_from_memory(image_data)
+                .map_err(|e| CredentialDetectorError::ImageLoadError(e.to_string()))?;
+            Ok(vec![TextLine::test_line("password: hunter2")])
+        });
+        BatchScanner::new(detector).with_threads(threads)
+    }
+
+    fn image(name: &str, data: &[u8]) -> BatchInput {
+        BatchInput::Image {
+            name: name.to_string(),
+            data: data.to_vec(),
+        }
+    }
+
+    #[test]
+    fn reports_each_input_by_index() {
+        let inputs = vec![
+            image("a.png", &png()),
+            image("broken.png", b"not an image"),
+            BatchInput::Path(PathBuf::from("/nonexistent/c.png")),
+            image("d.png", &png()),
+        ];
+
+        let mut results: Vec<BatchResult> = scanner(3).scan(inputs).collect();
+        results.sort_by_key(|result| result.index);
+
+        let names: Vec<&str> = results.iter().map(|result| result.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["a.png", "broken.png", "/nonexistent/c.png", "d.png"]
+        );
+        // Failing inputs do not abort the rest of the batch
+        assert_eq!(results[0].result.as_ref().unwrap().len(), 1);
+        assert!(results[1].result.is_err());
+        assert!(results[2].result.is_err());
+        assert_eq!(results[3].result.as_ref().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn handles_empty_batches() {
+        assert_eq!(scanner(4).scan(Vec::<BatchInput>::new()).count(), 0);
+    }
+
+    /// Endless inputs counting how many were pulled, signalling when dropped
+    struct Inputs {
+        data: Vec<u8>,
+        pulled: Arc<AtomicUsize>,
+        _dropped: mpsc::Sender<()>,
+    }
+
+    impl Iterator for Inputs {
+        type Item = BatchInput;
+
+        fn next(&mut self) -> Option<BatchInput> {
+            self.pulled.fetch_add(1, Ordering::SeqCst);
+            Some(image("a.png", &self.data))
+        }
+    }
+
+    #[test]
+    fn stops_pulling_inputs_when_results_are_dropped() {
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let (sender, dropped) = mpsc::channel();
+        let inputs = Inputs {
+            data: png(),
+            pulled: Arc::clone(&pulled),
+            _dropped: sender,
+        };
+
+        let mut results = scanner(2).scan(inputs);
+        assert!(results.next().unwrap().result.is_ok());
+        drop(results);
+
+        // The inputs are dropped once every worker has stopped
+        let stopped = dropped.recv_timeout(Duration::from_secs(30));
+        assert_eq!(stopped, Err(mpsc::RecvTimeoutError::Disconnected));
+        // One result was consumed, one buffered and one in progress per worker
+        assert!(pulled.load(Ordering::SeqCst) <= 1 + 2 + 2);
+    }
+}
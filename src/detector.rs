@@ -1,37 +1,130 @@
 //! Credential detector implementation
 
+use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 
-use crate::detectors::{ApiKeyDetector, CredentialDetectorLogic, PasswordDetector};
+use crate::decoding;
+use crate::detectors::{
+    ApiKeyDetector, ConnectionStringDetector, CredentialDetectorLogic, CreditCardDetector,
+    CryptoPrivateKeyDetector, EmailDetector, IbanDetector, JwtDetector, KeyValueDetector,
+    MyNumberDetector, PassportDetector, PasswordDetector, PhoneNumberDetector, QrPayloadDetector,
+    SeedPhraseDetector, SsnDetector,
+};
 use crate::error::CredentialDetectorError;
-use crate::ocr::OcrEngine;
-use crate::types::CredentialDetection;
+#[cfg(feature = "barcode")]
+use crate::ocr::BarcodeReader;
+use crate::ocr::{OcrEngine, TextExtractor, TextLine};
+use crate::raw_image::RawImage;
+use crate::sources::FrameSampling;
+#[cfg(feature = "archive")]
+use crate::sources::archive::{self, ArchiveLimits, ArchiveWalker};
+use crate::sources::document;
+#[cfg(feature = "email")]
+use crate::sources::email::{self, PartContent};
+use crate::sources::frames::{self, Frame, FrameSampler};
+#[cfg(feature = "git")]
+use crate::sources::git::GitHistory;
+#[cfg(feature = "pdf")]
+use crate::sources::pdf;
+#[cfg(feature = "video")]
+use crate::sources::video;
+use crate::types::{CredentialDetection, ScanReport};
+use image::DynamicImage;
+#[cfg(feature = "async")]
+use std::path::PathBuf;
+#[cfg(feature = "async")]
+use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "async")]
+use tokio::sync::Semaphore;
 
 /// Credential detector
+///
+/// The detector is `Send + Sync` and every detection method takes `&self`: load it once
+/// and share it between threads or async tasks in an [`Arc`](std::sync::Arc).
 pub struct CredentialDetector {
-    /// OCR engine
-    ocr_engine: OcrEngine,
+    /// Text extraction backend (OCR engine)
+    text_extractor: Box<dyn TextExtractor>,
+    /// QR code and barcode reader (`None` when disabled)
+    #[cfg(feature = "barcode")]
+    barcode_reader: Option<BarcodeReader>,
     /// List of credential detection logic
     detectors: Vec<Box<dyn CredentialDetectorLogic>>,
+    /// Maximum number of nested base64 encodings to unwrap (0 disables unwrapping)
+    max_decode_depth: usize,
+    /// Frame sampling for animations and multi-page images
+    frame_sampling: FrameSampling,
+    /// Limits on archive extraction
+    #[cfg(feature = "archive")]
+    archive_limits: ArchiveLimits,
+    /// Permits for scans running on the blocking thread pool of the async API
+    #[cfg(feature = "async")]
+    blocking_permits: Arc<Semaphore>,
+}
+
+/// Sets a cancellation flag when dropped, i.e. when the future awaiting a scan is dropped
+/// before the scan finishes
+#[cfg(feature = "async")]
+struct CancelOnDrop(Arc<AtomicBool>);
+
+#[cfg(feature = "async")]
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
 }
 
 impl CredentialDetector {
-    /// Create a new credential detector
+    /// Create a new credential detector using the default OCR engine (ocrs)
     pub fn new() -> Result<Self, CredentialDetectorError> {
         // Initialize OCR engine
         let ocr_engine = OcrEngine::new()?;
 
+        Ok(Self::from_text_extractor(ocr_engine))
+    }
+
+    /// Create a new credential detector using a configured OCR engine
+    ///
+    /// Use this to enable tiling of large images, preprocessing or rotation handling
+    /// (see [`OcrEngine::with_tiling`], [`OcrEngine::with_preprocessing`] and
+    /// [`OcrEngine::with_orientation`]).
+    pub fn from_ocr_engine(ocr_engine: OcrEngine) -> Self {
+        Self::from_text_extractor(ocr_engine)
+    }
+
+    /// Create a new credential detector using the given text extraction backend
+    ///
+    /// Use this to plug in another backend such as pre-computed OCR results.
+    pub fn from_text_extractor<E: TextExtractor + 'static>(text_extractor: E) -> Self {
         // Register default detectors
         let detectors: Vec<Box<dyn CredentialDetectorLogic>> = vec![
             Box::new(ApiKeyDetector::new()),
             Box::new(PasswordDetector::new()),
+            Box::new(JwtDetector::new()),
+            Box::new(KeyValueDetector::new()),
+            Box::new(ConnectionStringDetector::new()),
+            Box::new(CreditCardDetector::new()),
+            Box::new(SeedPhraseDetector::new()),
+            Box::new(CryptoPrivateKeyDetector::new()),
+            Box::new(QrPayloadDetector::new()),
         ];
 
-        Ok(Self {
-            ocr_engine,
+        Self {
+            text_extractor: Box::new(text_extractor),
+            #[cfg(feature = "barcode")]
+            barcode_reader: Some(BarcodeReader::new()),
             detectors,
-        })
+            max_decode_depth: 2,
+            frame_sampling: FrameSampling::default(),
+            #[cfg(feature = "archive")]
+            archive_limits: ArchiveLimits::default(),
+            #[cfg(feature = "async")]
+            blocking_permits: Arc::new(Semaphore::new(
+                std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+            )),
+        }
     }
 
     /// Add a custom detector
@@ -40,25 +133,695 @@ impl CredentialDetector {
         self
     }
 
+    /// Add the personally identifiable information (PII) detectors
+    ///
+    /// PII detectors are not registered by default. Individual PII detectors can also be
+    /// added with [`CredentialDetector::with_detector`].
+    pub fn with_pii_detectors(self) -> Self {
+        self.with_detector(Box::new(EmailDetector::new()))
+            .with_detector(Box::new(PhoneNumberDetector::new()))
+            .with_detector(Box::new(IbanDetector::new()))
+            .with_detector(Box::new(SsnDetector::new()))
+            .with_detector(Box::new(MyNumberDetector::new()))
+            .with_detector(Box::new(PassportDetector::new()))
+    }
+
+    /// Disable decoding QR codes and barcodes as an additional text source
+    #[cfg(feature = "barcode")]
+    pub fn without_barcodes(mut self) -> Self {
+        self.barcode_reader = None;
+        self
+    }
+
+    /// Set the maximum number of nested base64 encodings to unwrap (0 disables unwrapping)
+    pub fn with_max_decode_depth(mut self, max_decode_depth: usize) -> Self {
+        self.max_decode_depth = max_decode_depth;
+        self
+    }
+
+    /// Set the frame sampling for animated GIF/APNG, multi-page TIFF images and video
+    pub fn with_frame_sampling(mut self, frame_sampling: FrameSampling) -> Self {
+        self.frame_sampling = frame_sampling;
+        self
+    }
+
+    /// Set the limits on archive extraction (nesting depth, entry and total sizes)
+    #[cfg(feature = "archive")]
+    pub fn with_archive_limits(mut self, archive_limits: ArchiveLimits) -> Self {
+        self.archive_limits = archive_limits;
+        self
+    }
+
+    /// Set the maximum number of scans of the async API running at once
+    ///
+    /// Defaults to the number of available CPUs. Further calls wait for a running scan
+    /// to finish before being handed to the blocking thread pool.
+    #[cfg(feature = "async")]
+    pub fn with_max_blocking_scans(mut self, max_blocking_scans: usize) -> Self {
+        self.blocking_permits = Arc::new(Semaphore::new(max_blocking_scans.max(1)));
+        self
+    }
+
+    /// Remove the detectors with the given rule id (e.g. `"pii-email"`)
+    pub fn without_detector(mut self, rule_id: &str) -> Self {
+        self.detectors
+            .retain(|detector| detector.rule_id() != rule_id);
+        self
+    }
+
     /// Detect credentials from image data
+    ///
+    /// Every frame of animated GIF/APNG images and every page of multi-page TIFF images
+    /// is scanned (see [`CredentialDetector::with_frame_sampling`]); detections carry the
+    /// `frame_index` and, for animations, the `timestamp_ms` of the frame they were first
+    /// found in.
     pub fn detect_from_image(
         &self,
         image_data: &[u8],
     ) -> Result<Vec<CredentialDetection>, CredentialDetectorError> {
+        if let Some(frames) = frames::read_frames(image_data) {
+            return self.detect_from_frames(frames);
+        }
+
+        // Add the payloads of QR codes and barcodes, which OCR cannot read; the image is
+        // decoded once for both
+        #[cfg(feature = "barcode")]
+        if let Some(barcode_reader) = &self.barcode_reader {
+            let image = match image::load_from_memory(image_data) {
+                Ok(image) => image,
+                Err(e) => return Err(CredentialDetectorError::ImageLoadError(e.to_string())),
+            };
+            let mut text_lines = self
+                .text_extractor
+                .extract_text_from_decoded(image_data, &image)?;
+            text_lines.extend(barcode_reader.read_image(&image));
+            return Ok(self.detect_from_text_lines(&text_lines));
+        }
+
         // Extract text from image
-        let text_lines = self.ocr_engine.extract_text(image_data)?;
+        let text_lines = self.text_extractor.extract_text(image_data)?;
+        Ok(self.detect_from_text_lines(&text_lines))
+    }
+
+    /// Detect credentials from a decoded image
+    pub fn detect_from_decoded_image(
+        &self,
+        image: &DynamicImage,
+    ) -> Result<Vec<CredentialDetection>, CredentialDetectorError> {
+        #[allow(unused_mut)]
+        let mut text_lines = self.text_extractor.extract_text_from_image(image)?;
+
+        #[cfg(feature = "barcode")]
+        if let Some(barcode_reader) = &self.barcode_reader {
+            text_lines.extend(barcode_reader.read_image(image));
+        }
+
+        Ok(self.detect_from_text_lines(&text_lines))
+    }
+
+    /// Detect credentials from a video (H.264 in MP4 or MOV)
+    ///
+    /// Frames are sampled according to [`CredentialDetector::with_frame_sampling`] and
+    /// unchanged frames are skipped. Each finding is reported once, with the
+    /// `frame_index` and `timestamp_ms` of the frame it first appeared in.
+    /// Files without an H.264 track, such as HEVC or audio-only MP4, have no detections.
+    #[cfg(feature = "video")]
+    pub fn detect_from_video(
+        &self,
+        video_data: &[u8],
+    ) -> Result<Vec<CredentialDetection>, CredentialDetectorError> {
+        self.detect_from_frames(video::read_video(video_data)?)
+    }
+
+    /// Detect credentials from the images in an archive or office document
+    ///
+    /// Zip (including `.docx`, `.pptx`, `.xlsx` and `.epub`), tar and gzip files are
+    /// walked recursively within the [`ArchiveLimits`], which also cover archives nested
+    /// in emails. Each image, HTML or Markdown document (and PDF, video or email with
+    /// those features) is scanned; detections carry its nested `path`, such as
+    /// `deck.pptx!/ppt/media/image3.png`, starting with `name`. Entries that cannot be
+    /// read or scanned, such as corrupt images, and entries over the size or nesting
+    /// limits are skipped and listed in the report.
+    #[cfg(feature = "archive")]
+    pub fn detect_from_archive(
+        &self,
+        archive_data: &[u8],
+        name: &str,
+    ) -> Result<ScanReport, CredentialDetectorError> {
+        let mut walker = ArchiveWalker::new(&self.archive_limits);
+        let mut detections = self.scan_archive(archive_data, name, &mut walker)?;
+
+        sort_by_score(&mut detections);
+
+        Ok(ScanReport {
+            detections,
+            skipped: walker.into_skipped(),
+        })
+    }
+
+    /// Scan an archive with the walker of the containers it is nested in
+    #[cfg(feature = "archive")]
+    fn scan_archive(
+        &self,
+        archive_data: &[u8],
+        name: &str,
+        walker: &mut ArchiveWalker,
+    ) -> Result<Vec<CredentialDetection>, CredentialDetectorError> {
+        let mut detections = Vec::new();
+
+        walker.walk(archive_data, name, &mut |walker, path, data| {
+            if !is_scannable(data, path) {
+                return Ok(());
+            }
+
+            // The walker records the error and goes on with the rest of the archive
+            for mut detection in self.scan_nested(data, path, walker)? {
+                // Archives nested in emails have already recorded the nested path
+                detection
+                    .metadata
+                    .entry("path".to_string())
+                    .or_insert_with(|| path.to_string());
+                detections.push(detection);
+            }
+            Ok(())
+        })?;
+
+        Ok(detections)
+    }
+
+    /// Detect credentials from the images committed to a local git repository
+    ///
+    /// Walks every commit reachable from any reference, including images that were
+    /// later deleted. Each unique blob is scanned once; images are recognised by their
+    /// file extension. Detections carry the `commit` that first contained the blob, its
+    /// `path` and the commit's `author`. Blobs that cannot be read or scanned, such as
+    /// corrupt images, and blobs that are too large are skipped and listed in the report.
+    #[cfg(feature = "git")]
+    pub fn detect_from_git_repository<P: AsRef<Path>>(
+        &self,
+        repository_path: P,
+    ) -> Result<ScanReport, CredentialDetectorError> {
+        let history = GitHistory::open(repository_path.as_ref())?;
+        let mut detections = Vec::new();
+        let mut skipped = Vec::new();
+
+        for blob in history.blobs()? {
+            // A blob that cannot be read or scanned should not abort the scan of the rest
+            // of the history
+            let report = match history
+                .read(&blob)
+                .and_then(|data| self.scan_file_data(&data, &blob.path))
+            {
+                Ok(report) => report,
+                Err(e) => {
+                    skipped.push(e);
+                    continue;
+                }
+            };
+            skipped.extend(report.skipped);
+
+            for mut detection in report.detections {
+                // Archives have already recorded the nested path
+                detection
+                    .metadata
+                    .entry("path".to_string())
+                    .or_insert_with(|| blob.path.clone());
+                detection
+                    .metadata
+                    .insert("commit".to_string(), blob.commit.clone());
+                detection
+                    .metadata
+                    .insert("author".to_string(), blob.author.clone());
+                detections.push(detection);
+            }
+        }
+
+        sort_by_score(&mut detections);
+
+        Ok(ScanReport {
+            detections,
+            skipped,
+        })
+    }
+
+    /// Detect credentials from an HTML, Markdown or other text document
+    ///
+    /// Images embedded as base64 data URIs (`data:image/png;base64,...`) are decoded and
+    /// scanned; detections carry the `line` and byte `offset` of the URI in the document
+    /// and its `media_type`. Data URIs that are not valid images are skipped.
+    pub fn detect_from_document(
+        &self,
+        document: &str,
+    ) -> Result<Vec<CredentialDetection>, CredentialDetectorError> {
+        let mut detections = Vec::new();
+
+        for image in document::data_uri_images(document) {
+            let image_detections = match self.detect_from_image(&image.data) {
+                Ok(image_detections) => image_detections,
+                // SVG and truncated images cannot be scanned
+                Err(CredentialDetectorError::ImageLoadError(_)) => continue,
+                Err(e) => return Err(e),
+            };
+            for mut detection in image_detections {
+                detection
+                    .metadata
+                    .insert("line".to_string(), image.line.to_string());
+                detection
+                    .metadata
+                    .insert("offset".to_string(), image.offset.to_string());
+                detection
+                    .metadata
+                    .insert("media_type".to_string(), image.media_type.clone());
+                detections.push(detection);
+            }
+        }
+
+        sort_by_score(&mut detections);
+
+        Ok(detections)
+    }
+
+    /// Detect credentials from an email message (`.eml`)
+    ///
+    /// Attachments and inline images are scanned (archives, PDFs and videos too with
+    /// those features), as are data URIs in the text and HTML bodies. Attached messages
+    /// are descended into. Detections carry the `part_index` of the MIME part, the
+    /// `attachment` name (or Content-ID) when it has one, and the message's
+    /// `email_subject` and `email_from`. Parts that cannot be scanned, such as corrupt
+    /// attachments, are skipped and listed in the report.
+    #[cfg(feature = "email")]
+    pub fn detect_from_email(
+        &self,
+        eml_data: &[u8],
+    ) -> Result<ScanReport, CredentialDetectorError> {
+        let mut nesting = self.nesting();
+        let mut detections = self.scan_email(eml_data, "", &mut nesting)?;
+
+        sort_by_score(&mut detections);
+
+        Ok(ScanReport {
+            detections,
+            skipped: nesting.into_skipped(),
+        })
+    }
+
+    /// Scan an email named `name` within the limits of the containers it is nested in
+    #[cfg(feature = "email")]
+    fn scan_email(
+        &self,
+        eml_data: &[u8],
+        name: &str,
+        nesting: &mut Nesting,
+    ) -> Result<Vec<CredentialDetection>, CredentialDetectorError> {
+        // An email is a level of nesting like an archive, so that archives and emails
+        // nested in each other stay within `max_depth`
+        #[cfg(feature = "archive")]
+        {
+            nesting
+                .descend(name, |walker| self.scan_email_parts(eml_data, name, walker))
+                .unwrap_or_else(|| Ok(Vec::new()))
+        }
+        #[cfg(not(feature = "archive"))]
+        self.scan_email_parts(eml_data, name, nesting)
+    }
+
+    /// Scan the parts of an email, recording the parts that cannot be scanned
+    #[cfg(feature = "email")]
+    fn scan_email_parts(
+        &self,
+        eml_data: &[u8],
+        name: &str,
+        nesting: &mut Nesting,
+    ) -> Result<Vec<CredentialDetection>, CredentialDetectorError> {
+        let email = email::read_email(eml_data)?;
+        let mut detections = Vec::new();
+
+        for part in email.parts {
+            let part_name = part.name.as_deref().unwrap_or_default();
+            // Archives in the email report paths within the email's own path
+            let path = if name.is_empty() {
+                part_name.to_string()
+            } else {
+                format!("{}!/{}", name, part_name)
+            };
+            let part_detections = match &part.content {
+                PartContent::Text(text) => self.detect_from_document(text),
+                PartContent::Binary(data) if is_scannable(data, &path) => {
+                    self.scan_nested(data, &path, nesting)
+                }
+                PartContent::Binary(_) => continue,
+            };
+            // A corrupt attachment should not abort the scan of the rest of the message
+            let part_detections = match part_detections {
+                Ok(part_detections) => part_detections,
+                Err(e) => {
+                    nesting.skip(e);
+                    continue;
+                }
+            };
+
+            for mut detection in part_detections {
+                detection
+                    .metadata
+                    .insert("part_index".to_string(), part.index.to_string());
+                if let Some(name) = &part.name {
+                    detection
+                        .metadata
+                        .insert("attachment".to_string(), name.clone());
+                }
+                if let Some(subject) = &email.subject {
+                    detection
+                        .metadata
+                        .insert("email_subject".to_string(), subject.clone());
+                }
+                if let Some(from) = &email.from {
+                    detection
+                        .metadata
+                        .insert("email_from".to_string(), from.clone());
+                }
+                detections.push(detection);
+            }
+        }
+
+        Ok(detections)
+    }
+
+    /// Detect credentials from the contents of a file named `name`
+    pub(crate) fn detect_from_file_data(
+        &self,
+        data: &[u8],
+        name: &str,
+    ) -> Result<Vec<CredentialDetection>, CredentialDetectorError> {
+        Ok(self.scan_file_data(data, name)?.detections)
+    }
+
+    /// Scan the contents of a file named `name`, reporting the skipped entries of the
+    /// archives and emails it contains
+    pub(crate) fn scan_file_data(
+        &self,
+        data: &[u8],
+        name: &str,
+    ) -> Result<ScanReport, CredentialDetectorError> {
+        let mut nesting = self.nesting();
+        let mut detections = self.scan_nested(data, name, &mut nesting)?;
+
+        sort_by_score(&mut detections);
+
+        Ok(ScanReport {
+            detections,
+            skipped: nesting.into_skipped(),
+        })
+    }
+
+    /// State for the containers nested in one input
+    #[cfg(feature = "archive")]
+    fn nesting(&self) -> Nesting<'_> {
+        ArchiveWalker::new(&self.archive_limits)
+    }
+
+    /// State for the containers nested in one input
+    #[cfg(not(feature = "archive"))]
+    fn nesting(&self) -> Nesting {
+        Nesting::default()
+    }
+
+    /// Scan a file named `name`, which may be nested in other containers
+    #[cfg_attr(
+        not(any(feature = "archive", feature = "email")),
+        allow(unused_variables)
+    )]
+    fn scan_nested(
+        &self,
+        data: &[u8],
+        name: &str,
+        nesting: &mut Nesting,
+    ) -> Result<Vec<CredentialDetection>, CredentialDetectorError> {
+        #[cfg(feature = "archive")]
+        if archive::is_archive(data) {
+            return self.scan_archive(data, name, nesting);
+        }
+        #[cfg(feature = "email")]
+        if is_email(name) {
+            return self.scan_email(data, name, nesting);
+        }
+        if document::is_document(name) {
+            return self.detect_from_document(&String::from_utf8_lossy(data));
+        }
+
+        self.detect_from_data(data)
+    }
+
+    /// Detect credentials from a file's contents, according to its format
+    fn detect_from_data(
+        &self,
+        data: &[u8],
+    ) -> Result<Vec<CredentialDetection>, CredentialDetectorError> {
+        #[cfg(feature = "pdf")]
+        if data.starts_with(b"%PDF-") {
+            return self.detect_from_pdf(data);
+        }
+        #[cfg(feature = "video")]
+        if video::is_video(data) {
+            return self.detect_from_video(data);
+        }
+
+        // Detect credentials from image data
+        self.detect_from_image(data)
+    }
+
+    /// Detect credentials from a sequence of frames, reporting each finding once
+    ///
+    /// Only a failure to decode the first frame is an error; later bad frames end the
+    /// sequence.
+    fn detect_from_frames(
+        &self,
+        frames: impl Iterator<Item = Result<Frame, CredentialDetectorError>>,
+    ) -> Result<Vec<CredentialDetection>, CredentialDetectorError> {
+        let mut sampler = FrameSampler::new(&self.frame_sampling);
+        let mut seen = HashSet::new();
+        let mut detections = Vec::new();
+
+        let mut scan = |frame: Frame| -> Result<(), CredentialDetectorError> {
+            for mut detection in self.detect_from_decoded_image(&frame.image)? {
+                // A secret stays on screen for many frames; keep its first appearance
+                if !seen.insert((detection.rule_id.clone(), detection.text.clone())) {
+                    continue;
+                }
+
+                detection
+                    .metadata
+                    .insert("frame_index".to_string(), frame.index.to_string());
+                if let Some(timestamp) = frame.timestamp {
+                    detection.metadata.insert(
+                        "timestamp_ms".to_string(),
+                        timestamp.as_millis().to_string(),
+                    );
+                }
+                detections.push(detection);
+            }
+            Ok(())
+        };
+
+        for (decoded, frame) in frames.enumerate() {
+            if sampler.is_done() {
+                break;
+            }
+            let frame = match frame {
+                Ok(frame) => frame,
+                // A truncated or corrupt later frame ends the sequence; keep what the
+                // earlier frames gave
+                Err(_) if decoded > 0 => break,
+                Err(e) => return Err(e),
+            };
+            if let Some(frame) = sampler.push(frame) {
+                scan(frame)?;
+            }
+        }
+        if let Some(frame) = sampler.finish() {
+            scan(frame)?;
+        }
+
+        sort_by_score(&mut detections);
+
+        Ok(detections)
+    }
+
+    /// Detect credentials from a raw pixel buffer (e.g. a screen-capture frame)
+    pub fn detect_from_raw_image(
+        &self,
+        raw_image: &RawImage,
+    ) -> Result<Vec<CredentialDetection>, CredentialDetectorError> {
+        self.detect_from_decoded_image(&raw_image.to_image()?)
+    }
+
+    /// Detect credentials from a PDF document
+    ///
+    /// Scans the native text layer and OCRs the embedded raster images of every page.
+    /// Bounding boxes are in points relative to the top left of the page; detections
+    /// carry the `page` number, their `source` (`text_layer` or `image`) and, for
+    /// images, the `image_index` on the page.
+    #[cfg(feature = "pdf")]
+    pub fn detect_from_pdf(
+        &self,
+        pdf_data: &[u8],
+    ) -> Result<Vec<CredentialDetection>, CredentialDetectorError> {
+        let mut detections = Vec::new();
+
+        for page in pdf::read_pdf(pdf_data)? {
+            let page = page?;
+            let mut page_detections = Vec::new();
+
+            for mut detection in self.detect_from_text_lines(&page.text_lines) {
+                detection
+                    .metadata
+                    .insert("source".to_string(), "text_layer".to_string());
+                page_detections.push(detection);
+            }
 
+            for (index, placed_image) in page.images.iter().enumerate() {
+                for mut detection in self.detect_from_decoded_image(&placed_image.image)? {
+                    detection.bounding_box =
+                        placed_image.placement.map_box(&detection.bounding_box);
+                    detection
+                        .metadata
+                        .insert("source".to_string(), "image".to_string());
+                    detection
+                        .metadata
+                        .insert("image_index".to_string(), index.to_string());
+                    page_detections.push(detection);
+                }
+            }
+
+            for detection in &mut page_detections {
+                detection
+                    .metadata
+                    .insert("page".to_string(), page.number.to_string());
+            }
+            detections.append(&mut page_detections);
+        }
+
+        sort_by_score(&mut detections);
+
+        Ok(detections)
+    }
+
+    /// Detect credentials from already extracted text lines
+    pub fn detect_from_text_lines(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
         // Detect credentials using each detector
+        let mut detections = self.run_detectors(text_lines);
+
+        // Detect credentials hidden in base64 strings
+        for decoded_line in decoding::unwrap_base64(text_lines, self.max_decode_depth) {
+            let chain = decoded_line.encoding_chain.join(">");
+
+            for mut detection in self.run_detectors(std::slice::from_ref(&decoded_line.line)) {
+                // The decoded line keeps the surrounding text, which is already covered above
+                if !decoded_line.decoded.contains(&detection.text) {
+                    continue;
+                }
+
+                detection.bounding_box = decoded_line.bounding_box.clone();
+                detection
+                    .metadata
+                    .insert("encoding_chain".to_string(), chain.clone());
+                detection
+                    .metadata
+                    .insert("encoded_text".to_string(), decoded_line.encoded.clone());
+                detections.push(detection);
+            }
+        }
+
+        sort_by_score(&mut detections);
+
+        detections
+    }
+
+    /// Run every detector on the text lines
+    fn run_detectors(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
         let mut detections = Vec::new();
         for detector in &self.detectors {
-            let mut detector_results = detector.detect(&text_lines);
+            let mut detector_results = detector.detect(text_lines);
             detections.append(&mut detector_results);
         }
+        detections
+    }
 
-        // Sort by score in descending order
-        detections.sort_by(|a, b| b.credential_score.partial_cmp(&a.credential_score).unwrap());
+    /// Detect credentials from image data without blocking the async runtime
+    ///
+    /// OCR runs on tokio's blocking thread pool, with at most
+    /// [`CredentialDetector::with_max_blocking_scans`] scans at once; the future waits
+    /// while all are busy. Dropping the future before its scan has started cancels it;
+    /// a scan already running finishes and its result is discarded. Must be called
+    /// within a tokio runtime.
+    #[cfg(feature = "async")]
+    pub async fn detect_from_image_async(
+        self: &Arc<Self>,
+        image_data: Vec<u8>,
+    ) -> Result<Vec<CredentialDetection>, CredentialDetectorError> {
+        self.run_blocking(move |detector, _| detector.detect_from_image(&image_data))
+            .await
+    }
 
-        Ok(detections)
+    /// Detect credentials from file path without blocking the async runtime
+    ///
+    /// See [`CredentialDetector::detect_from_image_async`].
+    #[cfg(feature = "async")]
+    pub async fn detect_from_path_async<P: AsRef<Path>>(
+        self: &Arc<Self>,
+        path: P,
+    ) -> Result<Vec<CredentialDetection>, CredentialDetectorError> {
+        let path = PathBuf::from(path.as_ref());
+        self.run_blocking(move |detector, cancelled| {
+            let data = match fs::read(&path) {
+                Ok(data) => data,
+                Err(e) => return Err(CredentialDetectorError::FileNotFoundError(e.to_string())),
+            };
+            // Reading a large file may take long enough for the caller to give up
+            if cancelled.load(Ordering::Relaxed) {
+                return Ok(Vec::new());
+            }
+
+            let name = path.file_name().unwrap_or_default();
+            detector.detect_from_file_data(&data, &name.to_string_lossy())
+        })
+        .await
+    }
+
+    /// Run a scan on the blocking thread pool once a permit is available
+    #[cfg(feature = "async")]
+    async fn run_blocking<F>(
+        self: &Arc<Self>,
+        scan: F,
+    ) -> Result<Vec<CredentialDetection>, CredentialDetectorError>
+    where
+        F: FnOnce(
+                &CredentialDetector,
+                &AtomicBool,
+            ) -> Result<Vec<CredentialDetection>, CredentialDetectorError>
+            + Send
+            + 'static,
+    {
+        let permit = match Arc::clone(&self.blocking_permits).acquire_owned().await {
+            Ok(permit) => permit,
+            Err(e) => return Err(CredentialDetectorError::UnknownError(e.to_string())),
+        };
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let _cancel_on_drop = CancelOnDrop(Arc::clone(&cancelled));
+        let detector = Arc::clone(self);
+        let task = tokio::task::spawn_blocking(move || {
+            // Held until the scan finishes, even when the future was dropped
+            let _permit = permit;
+            if cancelled.load(Ordering::Relaxed) {
+                return Ok(Vec::new());
+            }
+            scan(&detector, &cancelled)
+        });
+
+        match task.await {
+            Ok(result) => result,
+            Err(e) => Err(CredentialDetectorError::UnknownError(e.to_string())),
+        }
     }
 
     /// Detect credentials from file path
@@ -67,12 +830,499 @@ impl CredentialDetector {
         path: P,
     ) -> Result<Vec<CredentialDetection>, CredentialDetectorError> {
         // Load image data from file
-        let image_data = match fs::read(path) {
+        let image_data = match fs::read(&path) {
             Ok(data) => data,
             Err(e) => return Err(CredentialDetectorError::FileNotFoundError(e.to_string())),
         };
 
-        // Detect credentials from image data
-        self.detect_from_image(&image_data)
+        let name = path.as_ref().file_name().unwrap_or_default();
+        self.detect_from_file_data(&image_data, &name.to_string_lossy())
+    }
+}
+
+/// State shared by the containers nested in one input
+///
+/// Archives and emails can contain each other. One state is threaded through every
+/// level, so that the archive limits apply to the whole input and the entries skipped
+/// at any level are reported. With the `archive` feature this is the archive walker.
+#[cfg(feature = "archive")]
+type Nesting<'a> = ArchiveWalker<'a>;
+
+/// State shared by the containers nested in one input: the entries that were skipped
+#[cfg(not(feature = "archive"))]
+#[derive(Default)]
+struct Nesting {
+    skipped: Vec<CredentialDetectorError>,
+}
+
+#[cfg(not(feature = "archive"))]
+impl Nesting {
+    /// Record an entry that was skipped
+    #[cfg(feature = "email")]
+    fn skip(&mut self, error: CredentialDetectorError) {
+        self.skipped.push(error);
+    }
+
+    /// Errors of the entries that were skipped
+    fn into_skipped(self) -> Vec<CredentialDetectorError> {
+        self.skipped
+    }
+}
+
+/// Determine if a file name has the `.eml` extension
+#[cfg(feature = "email")]
+fn is_email(name: &str) -> bool {
+    name.rsplit_once('.')
+        .is_some_and(|(_, extension)| extension.eq_ignore_ascii_case("eml"))
+}
+
+/// Sort detections by score in descending order
+///
+/// NaN scores from a custom detector go last instead of panicking.
+fn sort_by_score(detections: &mut [CredentialDetection]) {
+    detections.sort_by(|a, b| {
+        let (a, b) = (a.credential_score, b.credential_score);
+        a.is_nan().cmp(&b.is_nan()).then(b.total_cmp(&a))
+    });
+}
+
+/// Determine if an archive entry or attachment is in a format the detector can scan
+#[cfg(any(feature = "archive", feature = "email"))]
+fn is_scannable(data: &[u8], name: &str) -> bool {
+    if document::is_document(name) {
+        return true;
+    }
+    #[cfg(feature = "email")]
+    if is_email(name) {
+        return true;
+    }
+    #[cfg(feature = "archive")]
+    if archive::is_archive(data) {
+        return true;
+    }
+    #[cfg(feature = "pdf")]
+    if data.starts_with(b"%PDF-") {
+        return true;
+    }
+    #[cfg(feature = "video")]
+    if video::is_video(data) {
+        return true;
+    }
+
+    image::guess_format(data).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr::PrecomputedText;
+    #[cfg(feature = "async")]
+    use std::sync::atomic::AtomicUsize;
+    #[cfg(feature = "async")]
+    use std::sync::{Mutex, mpsc};
+
+    #[test]
+    fn built_in_rule_ids_are_unique() {
+        let detector = CredentialDetector::from_text_extractor(PrecomputedText::default())
+            .with_pii_detectors();
+
+        let mut rule_ids: Vec<&str> = detector
+            .detectors
+            .iter()
+            .map(|detector| detector.rule_id())
+            .collect();
+        let count = rule_ids.len();
+        rule_ids.sort_unstable();
+        rule_ids.dedup();
+        assert_eq!(rule_ids.len(), count, "duplicate rule ids: {rule_ids:?}");
+        assert!(rule_ids.contains(&"pii-email"));
+    }
+
+    #[test]
+    fn without_detector_removes_by_rule_id() {
+        let detector = CredentialDetector::from_text_extractor(PrecomputedText::default())
+            .with_pii_detectors()
+            .without_detector("pii-email");
+
+        assert!(
+            detector
+                .detectors
+                .iter()
+                .all(|detector| detector.rule_id() != "pii-email")
+        );
+        assert!(
+            detector
+                .detect_from_text_lines(&[TextLine::test_line("contact: alice@example.com")])
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn sorts_by_score_with_nan_scores() {
+        let detector = CredentialDetector::from_text_extractor(PrecomputedText::default());
+        let detection =
+            detector.detect_from_text_lines(&[TextLine::test_line("password: hunter2")])[0].clone();
+        let mut detections: Vec<_> = [0.5, f32::NAN, 0.9, -f32::NAN]
+            .into_iter()
+            .map(|score| CredentialDetection {
+                credential_score: score,
+                ..detection.clone()
+            })
+            .collect();
+
+        sort_by_score(&mut detections);
+        assert_eq!(detections[0].credential_score, 0.9);
+        assert_eq!(detections[1].credential_score, 0.5);
+        assert!(detections[2].credential_score.is_nan());
+        assert!(detections[3].credential_score.is_nan());
+    }
+
+    #[test]
+    fn reports_decoded_text_only_with_a_detector_hit() {
+        let detector = CredentialDetector::from_text_extractor(PrecomputedText::default());
+
+        // Decodes to "secret??????", which no detector flags
+        let detections =
+            detector.detect_from_text_lines(&[TextLine::test_line("c2VjcmV0Pz8/Pz8/")]);
+        assert!(detections.is_empty());
+
+        // base64(base64("password=Tr0ub4dor&3"))
+        let detections = detector.detect_from_text_lines(&[TextLine::test_line(
+            "Y0dGemMzZHZjbVE5VkhJd2RXSTBaRzl5SmpNPQ==",
+        )]);
+        let decoded = detections
+            .iter()
+            .find(|detection| detection.text == "Tr0ub4dor&3")
+            .unwrap();
+        assert_eq!(decoded.metadata["encoding_chain"], "base64>base64");
+        assert_eq!(
+            decoded.metadata["encoded_text"],
+            "Y0dGemMzZHZjbVE5VkhJd2RXSTBaRzl5SmpNPQ=="
+        );
+    }
+
+    /// Encode an animated GIF with one solid frame per gray level
+    fn gif(levels: &[u8]) -> Vec<u8> {
+        use image::codecs::gif::GifEncoder;
+        use image::{Delay, Rgba, RgbaImage};
+
+        let mut data = Vec::new();
+        let mut encoder = GifEncoder::new(&mut data);
+        encoder
+            .encode_frames(levels.iter().map(|&level| {
+                let image = RgbaImage::from_pixel(32, 32, Rgba([level, level, level, 255]));
+                image::Frame::from_parts(image, 0, 0, Delay::from_numer_denom_ms(100, 1))
+            }))
+            .unwrap();
+        drop(encoder);
+        data
+    }
+
+    #[test]
+    fn scans_still_gifs_as_plain_images() {
+        let detector = CredentialDetector::from_text_extractor(|image_data: &[u8]| {
+            // The encoded file is passed on rather than a decoded frame
+            assert!(image_data.starts_with(b"GIF"));
+            Ok(vec![TextLine::test_line("password: hunter2")])
+        });
+
+        let detections = detector.detect_from_image(&gif(&[0])).unwrap();
+        assert_eq!(detections.len(), 1);
+        assert!(!detections[0].metadata.contains_key("frame_index"));
+    }
+
+    #[test]
+    fn keeps_detections_before_a_corrupt_frame() {
+        let detector = CredentialDetector::from_text_extractor(|_: &[u8]| {
+            Ok(vec![TextLine::test_line("password: hunter2")])
+        })
+        .with_frame_sampling(FrameSampling::every_frame());
+
+        let data = gif(&[0, 255, 0]);
+        let detections = detector.detect_from_image(&data[..data.len() - 8]).unwrap();
+
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].metadata["frame_index"], "0");
+    }
+
+    #[cfg(feature = "archive")]
+    fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        use std::io::Write;
+        use zip::write::{SimpleFileOptions, ZipWriter};
+
+        let mut writer = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, data) in files {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[cfg(feature = "archive")]
+    #[test]
+    fn skips_archive_entries_that_fail_to_scan() {
+        let good = gif(&[0]);
+        let bad = gif(&[255]);
+        let truncated = good[..20].to_vec();
+        let archive = zip(&[
+            ("bad.gif", &bad),
+            ("truncated.gif", &truncated),
+            ("good.gif", &good),
+        ]);
+
+        let detector = CredentialDetector::from_text_extractor(move |image_data: &[u8]| {
+            // The truncated image fails to decode whether or not barcodes are read
+            image::load_from_memory(image_data)
+                .map_err(|e| CredentialDetectorError::ImageLoadError(e.to_string()))?;
+            if image_data == bad {
+                return Err(CredentialDetectorError::OcrProcessError(
+                    "failed".to_string(),
+                ));
+            }
+            Ok(vec![TextLine::test_line("password: hunter2")])
+        });
+
+        let report = detector.detect_from_archive(&archive, "a.zip").unwrap();
+        assert_eq!(report.detections.len(), 1);
+        assert_eq!(report.detections[0].metadata["path"], "a.zip!/good.gif");
+        assert_eq!(report.skipped.len(), 2);
+
+        // Skipped entries are reported when nothing was found
+        let report = detector
+            .detect_from_archive(&zip(&[("truncated.gif", &truncated)]), "a.zip")
+            .unwrap();
+        assert!(report.detections.is_empty());
+        assert_eq!(report.skipped.len(), 1);
+    }
+
+    #[cfg(all(feature = "archive", feature = "email"))]
+    #[test]
+    fn shares_archive_limits_with_nested_emails() {
+        use base64::Engine;
+        use base64::engine::general_purpose::STANDARD;
+
+        // zip → eml → zip → eml → ... each one inside the attachment of the previous one
+        let mut data = gif(&[0]);
+        let mut name = "image.gif";
+        for level in 0..4 {
+            data = zip(&[(name, &data)]);
+            let eml = format!(
+                "From: a@example.com\r\nSubject: level {level}\r\nMIME-Version: 1.0\r\n\
+                 Content-Type: multipart/mixed; boundary=b\r\n\r\n--b\r\n\
+                 Content-Type: text/plain\r\n\r\nsee attached\r\n--b\r\n\
+                 Content-Type: application/zip; name=inner.zip\r\n\
+                 Content-Disposition: attachment; filename=inner.zip\r\n\
+                 Content-Transfer-Encoding: base64\r\n\r\n{}\r\n--b--\r\n",
+                STANDARD.encode(&data)
+            );
+            data = eml.into_bytes();
+            name = "mail.eml";
+        }
+        let archive = zip(&[("mail.eml", &data)]);
+
+        let detector = CredentialDetector::from_text_extractor(|_: &[u8]| {
+            Ok(vec![TextLine::test_line("password: hunter2")])
+        });
+
+        let report = detector.detect_from_archive(&archive, "a.zip").unwrap();
+        assert!(report.detections.is_empty());
+        assert_eq!(report.skipped.len(), 1);
+        assert!(report.skipped[0].to_string().ends_with("nesting too deep"));
+
+        let detector = detector.with_archive_limits(ArchiveLimits {
+            max_depth: 8,
+            ..ArchiveLimits::default()
+        });
+        let report = detector.detect_from_archive(&archive, "a.zip").unwrap();
+        assert_eq!(report.detections.len(), 1);
+        assert!(report.skipped.is_empty());
+        assert_eq!(
+            report.detections[0].metadata["path"],
+            "a.zip!/mail.eml!/inner.zip!/mail.eml!/inner.zip!/mail.eml!/inner.zip!/mail.eml!/inner.zip!/image.gif"
+        );
+    }
+
+    #[cfg(feature = "git")]
+    #[test]
+    fn skips_git_blobs_that_fail_to_scan() {
+        use crate::sources::git::TestRepository;
+
+        let test = TestRepository::new("skips");
+        let good = gif(&[0]);
+        test.commit(&[("bad.gif", &good[..20]), ("good.gif", &good)]);
+
+        let detector = CredentialDetector::from_text_extractor(move |image_data: &[u8]| {
+            if image_data != good {
+                return Err(CredentialDetectorError::OcrProcessError(
+                    "failed".to_string(),
+                ));
+            }
+            Ok(vec![TextLine::test_line("password: hunter2")])
+        });
+        let report = detector
+            .detect_from_git_repository(&test.directory)
+            .unwrap();
+        assert_eq!(report.detections.len(), 1);
+        assert_eq!(report.detections[0].metadata["path"], "good.gif");
+        assert_eq!(report.skipped.len(), 1);
+
+        // Skipped blobs are reported when nothing was found
+        let test = TestRepository::new("skips-only");
+        test.commit(&[("bad.gif", b"GIF89a")]);
+        let report = detector
+            .detect_from_git_repository(&test.directory)
+            .unwrap();
+        assert!(report.detections.is_empty());
+        assert_eq!(report.skipped.len(), 1);
+    }
+
+    #[cfg(feature = "email")]
+    #[test]
+    fn skips_email_parts_that_fail_to_scan() {
+        use base64::Engine;
+        use base64::engine::general_purpose::STANDARD;
+
+        let good = gif(&[0]);
+        let attachment = |name: &str, data: &[u8]| {
+            format!(
+                "--b\r\nContent-Type: image/gif\r\nContent-Disposition: attachment; \
+                 filename=\"{}\"\r\nContent-Transfer-Encoding: base64\r\n\r\n{}\r\n",
+                name,
+                STANDARD.encode(data)
+            )
+        };
+        let eml = format!(
+            "From: alice@example.com\r\nSubject: Screenshots\r\nMIME-Version: 1.0\r\n\
+             Content-Type: multipart/mixed; boundary=\"b\"\r\n\r\n{}{}--b--\r\n",
+            attachment("bad.gif", &good[..20]),
+            attachment("good.gif", &good)
+        );
+
+        let detector = CredentialDetector::from_text_extractor(move |image_data: &[u8]| {
+            if image_data != good {
+                return Err(CredentialDetectorError::OcrProcessError(
+                    "failed".to_string(),
+                ));
+            }
+            Ok(vec![TextLine::test_line("password: hunter2")])
+        });
+
+        let report = detector.detect_from_email(eml.as_bytes()).unwrap();
+        assert_eq!(report.detections.len(), 1);
+        assert_eq!(report.detections[0].metadata["attachment"], "good.gif");
+        assert_eq!(
+            report.detections[0].metadata["email_subject"],
+            "Screenshots"
+        );
+        assert_eq!(report.skipped.len(), 1);
+
+        // Skipped parts are reported when nothing was found
+        let eml = format!(
+            "From: alice@example.com\r\nMIME-Version: 1.0\r\n\
+             Content-Type: multipart/mixed; boundary=\"b\"\r\n\r\n{}--b--\r\n",
+            attachment("bad.gif", &gif(&[0])[..20]),
+        );
+        let report = detector.detect_from_email(eml.as_bytes()).unwrap();
+        assert!(report.detections.is_empty());
+        assert_eq!(report.skipped.len(), 1);
+    }
+
+    /// Extractor that blocks until released, counting its calls
+    #[cfg(feature = "async")]
+    fn blocking_detector(
+        max_blocking_scans: usize,
+    ) -> (Arc<CredentialDetector>, Arc<AtomicUsize>, mpsc::Sender<()>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let (release, released) = mpsc::channel::<()>();
+        let released = Mutex::new(released);
+        let counter = Arc::clone(&calls);
+        let detector = CredentialDetector::from_text_extractor(move |_: &[u8]| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            released.lock().unwrap().recv().unwrap();
+            Ok(vec![TextLine::test_line("password: hunter2")])
+        })
+        .with_max_blocking_scans(max_blocking_scans);
+        (Arc::new(detector), calls, release)
+    }
+
+    /// Yield to the runtime until `condition` holds
+    #[cfg(feature = "async")]
+    async fn wait_until(condition: impl Fn() -> bool) {
+        for _ in 0..10_000 {
+            if condition() {
+                return;
+            }
+            tokio::task::yield_now().await;
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        panic!("condition not reached");
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn waits_for_a_free_blocking_permit() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let (detector, calls, release) = blocking_detector(1);
+
+        runtime.block_on(async {
+            let scans: Vec<_> = (0..2)
+                .map(|_| {
+                    let detector = Arc::clone(&detector);
+                    tokio::spawn(async move { detector.detect_from_image_async(gif(&[0])).await })
+                })
+                .collect();
+
+            wait_until(|| calls.load(Ordering::SeqCst) == 1).await;
+            // The second scan waits for the permit instead of starting
+            for _ in 0..50 {
+                tokio::task::yield_now().await;
+            }
+            assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+            release.send(()).unwrap();
+            wait_until(|| calls.load(Ordering::SeqCst) == 2).await;
+            release.send(()).unwrap();
+            for scan in scans {
+                assert_eq!(scan.await.unwrap().unwrap().len(), 1);
+            }
+        });
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn cancels_scans_whose_future_is_dropped() {
+        // A single blocking thread queues the second scan behind the first
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .max_blocking_threads(1)
+            .build()
+            .unwrap();
+        let (detector, calls, release) = blocking_detector(2);
+
+        runtime.block_on(async {
+            let running = {
+                let detector = Arc::clone(&detector);
+                tokio::spawn(async move { detector.detect_from_image_async(gif(&[0])).await })
+            };
+            wait_until(|| calls.load(Ordering::SeqCst) == 1).await;
+
+            let queued = {
+                let detector = Arc::clone(&detector);
+                tokio::spawn(async move { detector.detect_from_image_async(gif(&[0])).await })
+            };
+            // Both permits are taken once the second scan is on the blocking pool
+            wait_until(|| detector.blocking_permits.available_permits() == 0).await;
+            queued.abort();
+            assert!(queued.await.unwrap_err().is_cancelled());
+
+            release.send(()).unwrap();
+            assert_eq!(running.await.unwrap().unwrap().len(), 1);
+            // The cancelled scan gives its permit back without running
+            wait_until(|| detector.blocking_permits.available_permits() == 2).await;
+            assert_eq!(calls.load(Ordering::SeqCst), 1);
+        });
     }
 }
@@ -1,8 +1,8 @@
 //! API key detector implementation
 
-use crate::detectors::CredentialDetectorLogic;
+use crate::detectors::{CredentialDetectorLogic, SECRET_REMEDIATION_URL};
 use crate::ocr::TextLine;
-use crate::types::{BoundingBox, CredentialDetection};
+use crate::types::{BoundingBox, CredentialDetection, DetectorCategory, Severity};
 use regex::Regex;
 
 /// API key detector
@@ -31,11 +31,37 @@ impl ApiKeyDetector {
     }
 }
 
+impl Default for ApiKeyDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CredentialDetectorLogic for ApiKeyDetector {
     fn name(&self) -> &str {
         "API Key Detector"
     }
 
+    fn rule_id(&self) -> &str {
+        "api-key"
+    }
+
+    fn description(&self) -> &str {
+        "Cloud provider and SaaS API keys"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::High
+    }
+
+    fn category(&self) -> DetectorCategory {
+        DetectorCategory::CloudKey
+    }
+
+    fn remediation_url(&self) -> Option<&str> {
+        Some(SECRET_REMEDIATION_URL)
+    }
+
     fn detect(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
         let mut detections = Vec::new();
 
@@ -58,13 +84,13 @@ impl CredentialDetectorLogic for ApiKeyDetector {
                     // In a real implementation, a more sophisticated scoring logic would be used
                     let credential_score = 0.9;
 
-                    detections.push(CredentialDetection {
-                        text: matched_text,
+                    detections.push(CredentialDetection::new(
+                        self,
+                        matched_text,
                         bounding_box,
-                        text_detection_score: line.score,
+                        line.score,
                         credential_score,
-                        detector_name: self.name().to_string(),
-                    });
+                    ));
                 }
             }
         }
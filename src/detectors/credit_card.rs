@@ -0,0 +1,325 @@
+//! Payment card number detector implementation
+
+use crate::detectors::{CredentialDetectorLogic, PAYMENT_CARD_REMEDIATION_URL};
+use crate::ocr::TextLine;
+use crate::types::{CredentialDetection, DetectorCategory, Severity};
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// Issuer identification entry
+struct Issuer {
+    /// Issuer name
+    name: &'static str,
+    /// Inclusive ranges of leading digits (compared on the same number of digits)
+    prefixes: &'static [(u32, u32)],
+    /// Valid card number lengths
+    lengths: &'static [usize],
+}
+
+/// Known issuer prefixes and lengths
+const ISSUERS: &[Issuer] = &[
+    Issuer {
+        name: "Visa",
+        prefixes: &[(4, 4)],
+        lengths: &[13, 16, 19],
+    },
+    Issuer {
+        name: "Mastercard",
+        prefixes: &[(51, 55), (2221, 2720)],
+        lengths: &[16],
+    },
+    Issuer {
+        name: "American Express",
+        prefixes: &[(34, 34), (37, 37)],
+        lengths: &[15],
+    },
+    Issuer {
+        name: "Discover",
+        prefixes: &[(6011, 6011), (644, 649), (65, 65)],
+        lengths: &[16, 17, 18, 19],
+    },
+    Issuer {
+        name: "JCB",
+        prefixes: &[(3528, 3589)],
+        lengths: &[16, 17, 18, 19],
+    },
+    Issuer {
+        name: "Diners Club",
+        prefixes: &[(300, 305), (36, 36), (38, 39)],
+        lengths: &[14, 15, 16, 17, 18, 19],
+    },
+    Issuer {
+        name: "UnionPay",
+        prefixes: &[(62, 62)],
+        lengths: &[16, 17, 18, 19],
+    },
+    Issuer {
+        name: "Maestro",
+        prefixes: &[(50, 50), (56, 58), (63, 63), (67, 67)],
+        lengths: &[12, 13, 14, 15, 16, 17, 18, 19],
+    },
+];
+
+/// Payment card number detector
+pub struct CreditCardDetector {
+    /// Regular expression representing 12 or more digits, possibly separated by spaces or hyphens
+    pattern: Regex,
+}
+
+/// Digit of a candidate number and its position in the line
+struct Digit {
+    /// Digit value
+    value: u32,
+    /// Byte offset of the digit in the line
+    offset: usize,
+    /// Whether the digit starts a group (first digit or preceded by a separator)
+    group_start: bool,
+    /// Whether the digit ends a group (last digit or followed by a separator)
+    group_end: bool,
+}
+
+impl CreditCardDetector {
+    /// Create a new payment card number detector
+    pub fn new() -> Self {
+        // OCR often inserts extra spaces between digit groups or even between single digits.
+        // The match may be longer than a card number when other numbers are adjacent, so
+        // card-sized windows inside it are checked as well.
+        let pattern = Regex::new(r"\b\d(?:[ \-]{0,2}\d){11,}\b").unwrap();
+
+        Self { pattern }
+    }
+
+    /// Validate the check digit with the Luhn algorithm
+    fn luhn_valid(digits: &[u32]) -> bool {
+        let sum: u32 = digits
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, &d)| {
+                if i % 2 == 1 {
+                    let doubled = d * 2;
+                    if doubled > 9 { doubled - 9 } else { doubled }
+                } else {
+                    d
+                }
+            })
+            .sum();
+
+        sum.is_multiple_of(10)
+    }
+
+    /// Split a match into digits, remembering where the separated groups start and end
+    fn digits(text: &str, start: usize) -> Vec<Digit> {
+        let mut digits: Vec<Digit> = Vec::new();
+        let mut separated = true;
+
+        for (i, c) in text.char_indices() {
+            match c.to_digit(10) {
+                Some(value) => {
+                    if !separated && let Some(previous) = digits.last_mut() {
+                        previous.group_end = false;
+                    }
+                    digits.push(Digit {
+                        value,
+                        offset: start + i,
+                        group_start: separated,
+                        group_end: true,
+                    });
+                    separated = false;
+                }
+                None => separated = true,
+            }
+        }
+
+        digits
+    }
+
+    /// Find card numbers in a run of digits
+    ///
+    /// The whole run is tried first. Otherwise windows of 13-19 digits that start and end
+    /// on group boundaries are tried, longest first, so a card number next to an order or
+    /// phone number is still found.
+    fn find_numbers(digits: &[Digit]) -> Vec<(usize, usize, &'static str)> {
+        let valid = |window: &[Digit]| {
+            let values: Vec<u32> = window.iter().map(|d| d.value).collect();
+            if window.len() > 19 || !Self::luhn_valid(&values) {
+                return None;
+            }
+            Self::issuer(&values)
+        };
+
+        if let Some(issuer) = valid(digits) {
+            return vec![(0, digits.len(), issuer)];
+        }
+
+        let mut numbers = Vec::new();
+        let mut start = 0;
+        'start: while start < digits.len() {
+            if digits[start].group_start {
+                for length in (13..=19).rev() {
+                    let end = start + length;
+                    if end > digits.len() || !digits[end - 1].group_end {
+                        continue;
+                    }
+                    if let Some(issuer) = valid(&digits[start..end]) {
+                        numbers.push((start, end, issuer));
+                        start = end;
+                        continue 'start;
+                    }
+                }
+            }
+            start += 1;
+        }
+
+        numbers
+    }
+
+    /// Identify the issuer from the leading digits and the length
+    fn issuer(digits: &[u32]) -> Option<&'static str> {
+        ISSUERS
+            .iter()
+            .find(|issuer| {
+                issuer.lengths.contains(&digits.len())
+                    && issuer.prefixes.iter().any(|&(low, high)| {
+                        let width = low.to_string().len();
+                        let prefix = digits[..width].iter().fold(0, |acc, &d| acc * 10 + d);
+                        (low..=high).contains(&prefix)
+                    })
+            })
+            .map(|issuer| issuer.name)
+    }
+}
+
+impl Default for CreditCardDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialDetectorLogic for CreditCardDetector {
+    fn name(&self) -> &str {
+        "Credit Card Detector"
+    }
+
+    fn rule_id(&self) -> &str {
+        "credit-card"
+    }
+
+    fn description(&self) -> &str {
+        "Payment card numbers passing the Luhn check"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::High
+    }
+
+    fn category(&self) -> DetectorCategory {
+        DetectorCategory::PaymentCard
+    }
+
+    fn remediation_url(&self) -> Option<&str> {
+        Some(PAYMENT_CARD_REMEDIATION_URL)
+    }
+
+    fn detect(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
+        let mut detections = Vec::new();
+
+        for line in text_lines {
+            for matched in self.pattern.find_iter(&line.text) {
+                let digits = Self::digits(matched.as_str(), matched.start());
+
+                // Only numbers with a valid check digit and a known issuer are reported
+                for (first, last, issuer) in Self::find_numbers(&digits) {
+                    let start = digits[first].offset;
+                    let end = digits[last - 1].offset + 1;
+
+                    let mut metadata = BTreeMap::new();
+                    metadata.insert("issuer".to_string(), issuer.to_string());
+
+                    detections.push(
+                        CredentialDetection::new(
+                            self,
+                            line.text[start..end].to_string(),
+                            line.substring_box(start, end),
+                            line.score,
+                            0.95,
+                        )
+                        .with_metadata(metadata),
+                    );
+                }
+            }
+        }
+
+        detections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(text: &str) -> Vec<CredentialDetection> {
+        CreditCardDetector::new().detect(&[TextLine::test_line(text)])
+    }
+
+    fn values(number: &str) -> Vec<u32> {
+        number.chars().filter_map(|c| c.to_digit(10)).collect()
+    }
+
+    #[test]
+    fn luhn_check() {
+        assert!(CreditCardDetector::luhn_valid(&values("4111111111111111")));
+        assert!(CreditCardDetector::luhn_valid(&values("378282246310005")));
+        assert!(CreditCardDetector::luhn_valid(&values("5555555555554444")));
+        assert!(!CreditCardDetector::luhn_valid(&values("4111111111111112")));
+        assert!(!CreditCardDetector::luhn_valid(&values("1234567812345678")));
+    }
+
+    #[test]
+    fn issuer_from_prefix_and_length() {
+        assert_eq!(
+            CreditCardDetector::issuer(&values("4111111111111111")),
+            Some("Visa")
+        );
+        assert_eq!(
+            CreditCardDetector::issuer(&values("378282246310005")),
+            Some("American Express")
+        );
+        assert_eq!(
+            CreditCardDetector::issuer(&values("2221000000000009")),
+            Some("Mastercard")
+        );
+        // Visa numbers are never 15 digits long
+        assert_eq!(CreditCardDetector::issuer(&values("411111111111111")), None);
+    }
+
+    #[test]
+    fn detects_separated_numbers() {
+        let detections = detect("Card: 4111 1111 1111 1111 exp 12/30");
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].text, "4111 1111 1111 1111");
+        assert_eq!(detections[0].metadata["issuer"], "Visa");
+
+        let detections = detect("3782-822463-10005");
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].metadata["issuer"], "American Express");
+    }
+
+    #[test]
+    fn finds_numbers_next_to_other_digits() {
+        let detections = detect("Order 0312 4111 1111 1111 1111");
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].text, "4111 1111 1111 1111");
+
+        let detections = detect("4111 1111 1111 1111 5555 5555 5555 4444");
+        let texts: Vec<&str> = detections.iter().map(|d| d.text.as_str()).collect();
+        assert_eq!(texts, ["4111 1111 1111 1111", "5555 5555 5555 4444"]);
+    }
+
+    #[test]
+    fn ignores_invalid_numbers() {
+        assert!(detect("4111 1111 1111 1112").is_empty());
+        // A card number does not hide inside a longer unseparated run
+        assert!(detect("04111111111111111123").is_empty());
+    }
+}
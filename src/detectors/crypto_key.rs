@@ -0,0 +1,239 @@
+//! Cryptocurrency private key detector implementation
+
+use crate::detectors::{CredentialDetectorLogic, words};
+use crate::ocr::TextLine;
+use crate::types::{CredentialDetection, DetectorCategory, Severity};
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// Order of the secp256k1 curve; valid private keys are in `1..ORDER`
+const SECP256K1_ORDER: &str = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+
+/// Cryptocurrency private key detector
+///
+/// Detects WIF private keys and extended private keys (xprv, etc.) validated with their
+/// Base58Check checksum, and raw 256-bit hex keys.
+pub struct CryptoPrivateKeyDetector {
+    /// Regular expression representing WIF private keys
+    wif_pattern: Regex,
+    /// Regular expression representing BIP-32 extended private keys
+    extended_pattern: Regex,
+    /// Regular expression representing 256-bit hex keys
+    hex_pattern: Regex,
+    /// Keywords that make a hex string likely to be a private key rather than a hash
+    keywords: Vec<&'static str>,
+}
+
+impl CryptoPrivateKeyDetector {
+    /// Create a new cryptocurrency private key detector
+    pub fn new() -> Self {
+        let wif_pattern = Regex::new(r"\b[59KLc][1-9A-HJ-NP-Za-km-z]{50,51}\b").unwrap();
+        let extended_pattern = Regex::new(r"\b[xyzt]prv[1-9A-HJ-NP-Za-km-z]{100,108}\b").unwrap();
+        let hex_pattern = Regex::new(r"\b(?:0x)?([0-9a-fA-F]{64})\b").unwrap();
+
+        let keywords = vec!["private", "priv", "secret", "wallet", "key"];
+
+        Self {
+            wif_pattern,
+            extended_pattern,
+            hex_pattern,
+            keywords,
+        }
+    }
+
+    /// Decode a WIF key and return its network
+    fn wif_network(candidate: &str) -> Option<&'static str> {
+        let payload = bs58::decode(candidate).with_check(None).into_vec().ok()?;
+
+        // Version byte + 32-byte key, plus 0x01 for compressed public keys
+        let compressed = payload.len() == 34 && payload[33] == 0x01;
+        if payload.len() != 33 && !compressed {
+            return None;
+        }
+
+        match payload[0] {
+            0x80 => Some("mainnet"),
+            0xef => Some("testnet"),
+            _ => None,
+        }
+    }
+
+    /// Determine if a Base58Check string decodes to a serialized extended key
+    fn extended_key_valid(candidate: &str) -> bool {
+        bs58::decode(candidate)
+            .with_check(None)
+            .into_vec()
+            .is_ok_and(|payload| payload.len() == 78)
+    }
+
+    /// Determine if a 256-bit hex value is a valid secp256k1 private key
+    fn hex_key_valid(hex: &str) -> bool {
+        let hex = hex.to_lowercase();
+
+        // Equal-length hex strings compare like the numbers they represent
+        hex.chars().any(|c| c != '0') && hex.as_str() < SECP256K1_ORDER
+    }
+
+    /// Determine if text contains private key related keywords as whole words
+    fn contains_keyword(&self, text: &str) -> bool {
+        words(text)
+            .iter()
+            .any(|word| self.keywords.contains(&word.as_str()))
+    }
+}
+
+impl Default for CryptoPrivateKeyDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialDetectorLogic for CryptoPrivateKeyDetector {
+    fn name(&self) -> &str {
+        "Crypto Private Key Detector"
+    }
+
+    fn rule_id(&self) -> &str {
+        "crypto-private-key"
+    }
+
+    fn description(&self) -> &str {
+        "Cryptocurrency private keys (WIF, extended and hex keys)"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Critical
+    }
+
+    fn category(&self) -> DetectorCategory {
+        DetectorCategory::PrivateKey
+    }
+
+    fn detect(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
+        let mut detections = Vec::new();
+
+        for (i, line) in text_lines.iter().enumerate() {
+            let mut found = Vec::new();
+
+            for matched in self.wif_pattern.find_iter(&line.text) {
+                if let Some(network) = Self::wif_network(matched.as_str()) {
+                    found.push((matched, "wif", Some(network), 0.99, Severity::Critical));
+                }
+            }
+
+            for matched in self.extended_pattern.find_iter(&line.text) {
+                if Self::extended_key_valid(matched.as_str()) {
+                    found.push((matched, "extended", None, 0.99, Severity::Critical));
+                }
+            }
+
+            // Hex keys look like SHA-256 hashes and digests, so without a keyword nearby they
+            // are only reported with a low score and severity
+            let keyword_nearby = self.contains_keyword(&line.text)
+                || i > 0 && self.contains_keyword(&text_lines[i - 1].text);
+            for capture in self.hex_pattern.captures_iter(&line.text) {
+                let matched = capture.get(1).unwrap();
+                if Self::hex_key_valid(matched.as_str()) {
+                    let (score, severity) = if keyword_nearby {
+                        (0.9, Severity::Critical)
+                    } else {
+                        (0.2, Severity::Low)
+                    };
+                    found.push((matched, "hex", None, score, severity));
+                }
+            }
+
+            for (matched, key_type, network, credential_score, severity) in found {
+                let mut metadata = BTreeMap::new();
+                metadata.insert("key_type".to_string(), key_type.to_string());
+                if let Some(network) = network {
+                    metadata.insert("network".to_string(), network.to_string());
+                }
+
+                detections.push(
+                    CredentialDetection::new(
+                        self,
+                        matched.as_str().to_string(),
+                        line.substring_box(matched.start(), matched.end()),
+                        line.score,
+                        credential_score,
+                    )
+                    .with_severity(severity)
+                    .with_metadata(metadata),
+                );
+            }
+        }
+
+        detections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(texts: &[&str]) -> Vec<TextLine> {
+        texts.iter().map(|text| TextLine::test_line(text)).collect()
+    }
+
+    const HEX_KEY: &str = "e9873d79c6d87dc0fb6a5778633389f4453213303da61f20bd67fc233aa33262";
+
+    #[test]
+    fn detects_wif_keys() {
+        let detections = CryptoPrivateKeyDetector::new().detect(&lines(&[
+            "5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ",
+        ]));
+
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].metadata["key_type"], "wif");
+        assert_eq!(detections[0].metadata["network"], "mainnet");
+        assert_eq!(detections[0].severity, Severity::Critical);
+    }
+
+    #[test]
+    fn rejects_wif_keys_with_bad_checksum() {
+        let detections = CryptoPrivateKeyDetector::new().detect(&lines(&[
+            "5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTK",
+        ]));
+
+        assert!(detections.is_empty());
+    }
+
+    #[test]
+    fn hex_keys_need_a_keyword() {
+        let detector = CryptoPrivateKeyDetector::new();
+
+        let text = format!("PRIVATE_KEY={HEX_KEY}");
+        let detections = detector.detect(&lines(&[&text]));
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].credential_score, 0.9);
+        assert_eq!(detections[0].severity, Severity::Critical);
+
+        // Keyword on the previous line
+        let detections = detector.detect(&lines(&["Wallet private key:", HEX_KEY]));
+        assert_eq!(detections[0].severity, Severity::Critical);
+    }
+
+    #[test]
+    fn hex_without_keyword_is_low_severity() {
+        let detector = CryptoPrivateKeyDetector::new();
+
+        for text in [
+            format!("sha256: {HEX_KEY}"),
+            format!("monkey {HEX_KEY}"),
+            format!("turkey-digest {HEX_KEY}"),
+        ] {
+            let detections = detector.detect(&lines(&[&text]));
+            assert_eq!(detections.len(), 1, "{text}");
+            assert_eq!(detections[0].credential_score, 0.2, "{text}");
+            assert_eq!(detections[0].severity, Severity::Low, "{text}");
+        }
+    }
+
+    #[test]
+    fn hex_key_range() {
+        assert!(!CryptoPrivateKeyDetector::hex_key_valid(&"0".repeat(64)));
+        assert!(!CryptoPrivateKeyDetector::hex_key_valid(SECP256K1_ORDER));
+        assert!(CryptoPrivateKeyDetector::hex_key_valid(HEX_KEY));
+    }
+}
@@ -0,0 +1,297 @@
+//! Structured key=value secret detector implementation
+
+use crate::detectors::{CredentialDetectorLogic, SECRET_REMEDIATION_URL, words};
+use crate::ocr::TextLine;
+use crate::types::{CredentialDetection, Severity};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+
+/// Structured key=value secret detector
+///
+/// Parses assignment-shaped text such as `.env` lines (`SECRET_KEY=...`), YAML
+/// (`password: ...`) and JSON (`"client_secret": "..."`), and reports values whose
+/// key name looks sensitive. Values are scored on their entropy.
+pub struct KeyValueDetector {
+    /// Regular expression representing a key, a `=` or `:` separator and a (quoted) value,
+    /// skipping HTTP authentication schemes (`Authorization: Bearer ...`)
+    pattern: Regex,
+    /// Words making a key name sensitive, including common compounds written as one word
+    sensitive_words: Vec<&'static str>,
+    /// Words marking a key as a reference to or a property of a secret rather than the secret
+    excluded_words: Vec<&'static str>,
+    /// Values that are obviously placeholders
+    placeholders: Vec<&'static str>,
+}
+
+impl KeyValueDetector {
+    /// Create a new key=value secret detector
+    pub fn new() -> Self {
+        let pattern = Regex::new(
+            r#"(?:^|[\s{,;(])(?:export\s+)?["']?([A-Za-z_][A-Za-z0-9_.\-]*)["']?\s*(?::=|=|:)\s*(?:(?:Basic|Bearer|Token|Digest)\s+)?(?:"((?:[^"\\]|\\.)*)"|'([^']*)'|([^\s"',;}]+))"#,
+        )
+        .unwrap();
+
+        let sensitive_words = vec![
+            "secret",
+            "token",
+            "key",
+            "credential",
+            "credentials",
+            "auth",
+            "authorization",
+            "private",
+            "passphrase",
+            "password",
+            "passwd",
+            "pwd",
+            "apikey",
+            "accesskey",
+            "secretkey",
+            "privatekey",
+            "accesstoken",
+            "authtoken",
+            "clientsecret",
+        ];
+
+        let excluded_words = vec![
+            "public",
+            "primary",
+            "foreign",
+            "partition",
+            "sort",
+            "cache",
+            "id",
+            "name",
+            "type",
+            "file",
+            "path",
+            "url",
+            "uri",
+            "endpoint",
+            "length",
+            "size",
+        ];
+
+        let placeholders = vec![
+            "null",
+            "none",
+            "nil",
+            "true",
+            "false",
+            "undefined",
+            "changeme",
+            "change_me",
+            "your_secret_here",
+            "your_api_key",
+            "todo",
+            "redacted",
+            "example",
+        ];
+
+        Self {
+            pattern,
+            sensitive_words,
+            excluded_words,
+            placeholders,
+        }
+    }
+
+    /// Determine if a key name looks sensitive (e.g. `SECRET_KEY`, `clientSecret`, `apikey`)
+    fn is_sensitive_key(&self, key: &str) -> bool {
+        // Whole `_`, `-` and camelCase segments are compared, so `monkey` or `oauth` do
+        // not match `key` or `auth`
+        let words = words(key);
+
+        // `PUBLIC_KEY`, `cache_key` or `secret_name` do not hold the secret itself
+        if words
+            .iter()
+            .any(|word| self.excluded_words.contains(&word.as_str()))
+        {
+            return false;
+        }
+
+        words
+            .iter()
+            .any(|word| self.sensitive_words.contains(&word.as_str()))
+    }
+
+    /// Determine if a value is a placeholder rather than a real secret
+    fn is_placeholder(&self, value: &str) -> bool {
+        let lower = value.to_lowercase();
+
+        value.starts_with("${")
+            || value.starts_with("{{")
+            || value.starts_with('<')
+            || value
+                .chars()
+                .all(|c| matches!(c, '*' | 'x' | 'X' | '.' | '-'))
+            || self.placeholders.contains(&lower.as_str())
+    }
+
+    /// Calculate the Shannon entropy of a value in bits per character
+    fn shannon_entropy(value: &str) -> f32 {
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for c in value.chars() {
+            *counts.entry(c).or_default() += 1;
+        }
+
+        let length = value.chars().count() as f32;
+        counts
+            .values()
+            .map(|&count| {
+                let p = count as f32 / length;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    /// Calculate credential score from the value's entropy
+    fn score(value: &str, entropy: f32) -> f32 {
+        if value.len() < 8 {
+            return 0.5;
+        }
+
+        match entropy {
+            e if e >= 4.0 => 0.95,
+            e if e >= 3.0 => 0.85,
+            e if e >= 2.0 => 0.65,
+            _ => 0.4,
+        }
+    }
+}
+
+impl Default for KeyValueDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialDetectorLogic for KeyValueDetector {
+    fn name(&self) -> &str {
+        "Key-Value Secret Detector"
+    }
+
+    fn rule_id(&self) -> &str {
+        "key-value-secret"
+    }
+
+    fn description(&self) -> &str {
+        "Values assigned to secret-looking keys in config files"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::High
+    }
+
+    fn remediation_url(&self) -> Option<&str> {
+        Some(SECRET_REMEDIATION_URL)
+    }
+
+    fn detect(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
+        let mut detections = Vec::new();
+
+        for line in text_lines {
+            for capture in self.pattern.captures_iter(&line.text) {
+                let key = &capture[1];
+                // Exactly one of the double-quoted, single-quoted and bare value groups matches
+                let value = capture
+                    .get(2)
+                    .or_else(|| capture.get(3))
+                    .or_else(|| capture.get(4))
+                    .unwrap();
+
+                // `scheme://...` is a URL rather than a `key: value` pair
+                if value.as_str().len() < 4
+                    || value.as_str().starts_with("//")
+                    || !self.is_sensitive_key(key)
+                    || self.is_placeholder(value.as_str())
+                {
+                    continue;
+                }
+
+                let entropy = Self::shannon_entropy(value.as_str());
+
+                let mut metadata = BTreeMap::new();
+                metadata.insert("key".to_string(), key.to_string());
+                metadata.insert("entropy".to_string(), format!("{:.2}", entropy));
+
+                detections.push(
+                    CredentialDetection::new(
+                        self,
+                        value.as_str().to_string(),
+                        line.substring_box(value.start(), value.end()),
+                        line.score,
+                        Self::score(value.as_str(), entropy),
+                    )
+                    .with_metadata(metadata),
+                );
+            }
+        }
+
+        detections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(text: &str) -> Vec<CredentialDetection> {
+        KeyValueDetector::new().detect(&[TextLine::test_line(text)])
+    }
+
+    #[test]
+    fn sensitive_keys() {
+        let detector = KeyValueDetector::new();
+        for key in [
+            "SECRET_KEY",
+            "api-key",
+            "clientSecret",
+            "apikey",
+            "AWS_ACCESS_TOKEN",
+            "db.password",
+            "oauth_token",
+        ] {
+            assert!(detector.is_sensitive_key(key), "{key}");
+        }
+    }
+
+    #[test]
+    fn keys_containing_sensitive_substrings() {
+        let detector = KeyValueDetector::new();
+        for key in [
+            "monkey",
+            "turkey_count",
+            "oauth",
+            "cache_key",
+            "primaryKey",
+            "sort-key",
+            "PUBLIC_KEY",
+            "secret_name",
+            "keyboard",
+        ] {
+            assert!(!detector.is_sensitive_key(key), "{key}");
+        }
+    }
+
+    #[test]
+    fn detects_assignments() {
+        let detections = detect("export SECRET_KEY=\"q8Zr2LmX4vT9pW1s\"");
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].text, "q8Zr2LmX4vT9pW1s");
+        assert_eq!(detections[0].metadata["key"], "SECRET_KEY");
+
+        let detections = detect("{\"client_secret\": \"c2VjcmV0LXZhbHVl\"}");
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].text, "c2VjcmV0LXZhbHVl");
+    }
+
+    #[test]
+    fn ignores_placeholders_and_urls() {
+        assert!(detect("API_KEY=${API_KEY}").is_empty());
+        assert!(detect("password: changeme").is_empty());
+        assert!(detect("token: ********").is_empty());
+        assert!(detect("see secret://vault.example.com/prod/db").is_empty());
+        assert!(detect("monkey=q8Zr2LmX4vT9pW1s").is_empty());
+    }
+}
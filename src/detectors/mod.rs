@@ -1,19 +1,256 @@
 //! Credential detector implementations
 
 mod api_key;
+mod connection_string;
+mod credit_card;
+mod crypto_key;
+mod jwt;
+mod key_value;
 mod password;
+mod pii;
+mod qr_payload;
+mod seed_phrase;
 
 pub use api_key::ApiKeyDetector;
+pub use connection_string::ConnectionStringDetector;
+pub use credit_card::CreditCardDetector;
+pub use crypto_key::CryptoPrivateKeyDetector;
+pub use jwt::JwtDetector;
+pub use key_value::KeyValueDetector;
 pub use password::PasswordDetector;
+pub use pii::{
+    EmailDetector, IbanDetector, MyNumberDetector, PassportDetector, PhoneNumberDetector,
+    SsnDetector,
+};
+pub use qr_payload::QrPayloadDetector;
+pub use seed_phrase::SeedPhraseDetector;
 
 use crate::ocr::TextLine;
-use crate::types::CredentialDetection;
+use crate::types::{CredentialDetection, DetectorCategory, Severity};
+use std::sync::Arc;
+
+/// Remediation guide for leaked secrets (rotation and secret management)
+pub(crate) const SECRET_REMEDIATION_URL: &str =
+    "https://cheatsheetseries.owasp.org/cheatsheets/Secrets_Management_Cheat_Sheet.html";
+
+/// Remediation guide for exposed personal information
+pub(crate) const PII_REMEDIATION_URL: &str =
+    "https://cheatsheetseries.owasp.org/cheatsheets/User_Privacy_Protection_Cheat_Sheet.html";
+
+/// Remediation guide for exposed payment card data
+pub(crate) const PAYMENT_CARD_REMEDIATION_URL: &str = "https://www.pcisecuritystandards.org/";
+
+/// Split text into lowercase words at non-alphanumeric characters and camelCase humps
+///
+/// `PRIVATE_KEY`, `private-key` and `privateKey` all give `["private", "key"]`, so keywords
+/// can be matched as whole words instead of substrings (`monkey` does not contain `key`).
+pub(crate) fn words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let chars: Vec<char> = text.chars().collect();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        // `fooBar` and `APIKey` split before the upper case letter starting a new word
+        let previous = i.checked_sub(1).map(|j| chars[j]);
+        let next = chars.get(i + 1);
+        let hump = c.is_uppercase()
+            && previous.is_some_and(|p| {
+                p.is_lowercase()
+                    || p.is_ascii_digit()
+                    || p.is_uppercase() && next.is_some_and(|n| n.is_lowercase())
+            });
+        if hump && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+
+        current.extend(c.to_lowercase());
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
 
 /// Credential detector interface
-pub trait CredentialDetectorLogic {
+///
+/// A [`CredentialDetector`](crate::CredentialDetector) may be shared by several threads,
+/// which then call [`CredentialDetectorLogic::detect`] concurrently; implementations
+/// must be `Send + Sync` and keep per-call state local. Wrap a detector in an [`Arc`] to
+/// register it with several credential detectors.
+pub trait CredentialDetectorLogic: Send + Sync {
     /// Returns the name of the detector
     fn name(&self) -> &str;
 
+    /// Returns the stable identifier of the detection rule
+    ///
+    /// Unlike the name, the identifier is meant for routing and suppression lists and
+    /// does not change between versions. Defaults to the name.
+    fn rule_id(&self) -> &str {
+        self.name()
+    }
+
+    /// Returns a human-readable description of what the detector finds
+    fn description(&self) -> &str {
+        self.name()
+    }
+
+    /// Returns the severity of the detections
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    /// Returns the category of information the detector looks for
+    fn category(&self) -> DetectorCategory {
+        DetectorCategory::GenericSecret
+    }
+
+    /// Returns a URL describing how to remediate a leak
+    fn remediation_url(&self) -> Option<&str> {
+        None
+    }
+
     /// Detects credentials from text lines
     fn detect(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection>;
 }
+
+impl<T: CredentialDetectorLogic + ?Sized> CredentialDetectorLogic for Arc<T> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn rule_id(&self) -> &str {
+        (**self).rule_id()
+    }
+
+    fn description(&self) -> &str {
+        (**self).description()
+    }
+
+    fn severity(&self) -> Severity {
+        (**self).severity()
+    }
+
+    fn category(&self) -> DetectorCategory {
+        (**self).category()
+    }
+
+    fn remediation_url(&self) -> Option<&str> {
+        (**self).remediation_url()
+    }
+
+    fn detect(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
+        (**self).detect(text_lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_identifiers_into_words() {
+        assert_eq!(words("PRIVATE_KEY"), ["private", "key"]);
+        assert_eq!(words("wallet-private-key:"), ["wallet", "private", "key"]);
+        assert_eq!(words("privKey"), ["priv", "key"]);
+        assert_eq!(
+            words("AWSSecretAccessKey"),
+            ["aws", "secret", "access", "key"]
+        );
+        assert_eq!(words("api2Key = 1"), ["api2", "key", "1"]);
+        assert_eq!(words("monkey"), ["monkey"]);
+        assert!(words(" = ").is_empty());
+    }
+
+    /// Detector overriding every piece of rule information
+    struct TokenDetector;
+
+    impl CredentialDetectorLogic for TokenDetector {
+        fn name(&self) -> &str {
+            "Example Token"
+        }
+
+        fn rule_id(&self) -> &str {
+            "example-token"
+        }
+
+        fn description(&self) -> &str {
+            "Example service token"
+        }
+
+        fn severity(&self) -> Severity {
+            Severity::Critical
+        }
+
+        fn category(&self) -> DetectorCategory {
+            DetectorCategory::Token
+        }
+
+        fn remediation_url(&self) -> Option<&str> {
+            Some(SECRET_REMEDIATION_URL)
+        }
+
+        fn detect(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
+            text_lines
+                .iter()
+                .map(|line| {
+                    let bounding_box = line.substring_box(0, line.text.len());
+                    CredentialDetection::new(self, line.text.clone(), bounding_box, 1.0, 0.9)
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn detections_carry_the_rule_information() {
+        let lines = [TextLine::test_line("tok_123")];
+
+        // Registered directly and shared through an `Arc`
+        let detections = [
+            TokenDetector.detect(&lines),
+            Arc::new(TokenDetector).detect(&lines),
+        ];
+        for detection in detections.iter().flatten() {
+            assert_eq!(detection.detector_name, "Example Token");
+            assert_eq!(detection.rule_id, "example-token");
+            assert_eq!(detection.description, "Example service token");
+            assert_eq!(detection.severity, Severity::Critical);
+            assert_eq!(detection.category, DetectorCategory::Token);
+            assert_eq!(
+                detection.remediation_url.as_deref(),
+                Some(SECRET_REMEDIATION_URL)
+            );
+        }
+        assert_eq!(detections.iter().flatten().count(), 2);
+    }
+
+    /// Detector relying on the default rule information
+    struct NamedDetector;
+
+    impl CredentialDetectorLogic for NamedDetector {
+        fn name(&self) -> &str {
+            "named"
+        }
+
+        fn detect(&self, _text_lines: &[TextLine]) -> Vec<CredentialDetection> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn rule_information_defaults_to_the_name() {
+        assert_eq!(NamedDetector.rule_id(), "named");
+        assert_eq!(NamedDetector.description(), "named");
+        assert_eq!(NamedDetector.severity(), Severity::Medium);
+        assert_eq!(NamedDetector.category(), DetectorCategory::GenericSecret);
+        assert_eq!(NamedDetector.remediation_url(), None);
+    }
+}
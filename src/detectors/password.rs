@@ -1,8 +1,8 @@
 //! Password detector implementation
 
-use crate::detectors::CredentialDetectorLogic;
+use crate::detectors::{CredentialDetectorLogic, SECRET_REMEDIATION_URL};
 use crate::ocr::TextLine;
-use crate::types::{BoundingBox, CredentialDetection};
+use crate::types::{BoundingBox, CredentialDetection, DetectorCategory, Severity};
 use regex::Regex;
 
 /// Password detector
@@ -38,11 +38,37 @@ impl PasswordDetector {
     }
 }
 
+impl Default for PasswordDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CredentialDetectorLogic for PasswordDetector {
     fn name(&self) -> &str {
         "Password Detector"
     }
 
+    fn rule_id(&self) -> &str {
+        "password"
+    }
+
+    fn description(&self) -> &str {
+        "Passwords next to password labels"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::High
+    }
+
+    fn category(&self) -> DetectorCategory {
+        DetectorCategory::Password
+    }
+
+    fn remediation_url(&self) -> Option<&str> {
+        Some(SECRET_REMEDIATION_URL)
+    }
+
     fn detect(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
         let mut detections = Vec::new();
 
@@ -58,13 +84,11 @@ impl CredentialDetectorLogic for PasswordDetector {
                 let start = i.saturating_sub(WINDOW_SIZE);
                 let end = (i + WINDOW_SIZE + 1).min(text_lines.len());
 
-                for j in start..end {
+                for (j, line) in text_lines.iter().enumerate().take(end).skip(start) {
                     if i == j {
                         continue; // Skip the line containing the keyword itself
                     }
 
-                    let line = &text_lines[j];
-
                     // Search for parts matching the password pattern
                     for capture in self.pattern.captures_iter(&line.text) {
                         let matched_text = capture[0].to_string();
@@ -81,13 +105,13 @@ impl CredentialDetectorLogic for PasswordDetector {
                         let distance = (i as isize - j as isize).abs() as f32;
                         let credential_score = 0.9 - (distance * 0.1).min(0.4);
 
-                        detections.push(CredentialDetection {
-                            text: matched_text,
+                        detections.push(CredentialDetection::new(
+                            self,
+                            matched_text,
                             bounding_box,
-                            text_detection_score: line.score,
+                            line.score,
                             credential_score,
-                            detector_name: self.name().to_string(),
-                        });
+                        ));
                     }
                 }
             }
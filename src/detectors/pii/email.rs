@@ -0,0 +1,127 @@
+//! Email address detector implementation
+
+use crate::detectors::{CredentialDetectorLogic, PII_REMEDIATION_URL};
+use crate::ocr::TextLine;
+use crate::types::{CredentialDetection, DetectorCategory, Severity};
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// Image file extensions, which are not top-level domains
+///
+/// Scaled assets such as `icon@2x.png` otherwise look like addresses.
+const IMAGE_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "gif", "svg", "webp"];
+
+/// Email address detector
+pub struct EmailDetector {
+    /// Regular expression representing email addresses
+    pattern: Regex,
+}
+
+impl EmailDetector {
+    /// Create a new email address detector
+    pub fn new() -> Self {
+        let pattern =
+            Regex::new(r"\b[A-Za-z0-9._%+\-]+@[A-Za-z0-9\-]+(?:\.[A-Za-z0-9\-]+)*\.[A-Za-z]{2,}\b")
+                .unwrap();
+
+        Self { pattern }
+    }
+}
+
+impl Default for EmailDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialDetectorLogic for EmailDetector {
+    fn name(&self) -> &str {
+        "Email Address Detector"
+    }
+
+    fn rule_id(&self) -> &str {
+        "pii-email"
+    }
+
+    fn description(&self) -> &str {
+        "Email addresses"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Low
+    }
+
+    fn category(&self) -> DetectorCategory {
+        DetectorCategory::Pii
+    }
+
+    fn remediation_url(&self) -> Option<&str> {
+        Some(PII_REMEDIATION_URL)
+    }
+
+    fn detect(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
+        let mut detections = Vec::new();
+
+        for line in text_lines {
+            for matched in self.pattern.find_iter(&line.text) {
+                let domain = matched.as_str().rsplit('@').next().unwrap_or_default();
+                let top_level = domain.rsplit('.').next().unwrap_or_default();
+                if IMAGE_EXTENSIONS.contains(&top_level.to_lowercase().as_str()) {
+                    continue;
+                }
+
+                let mut metadata = BTreeMap::new();
+                metadata.insert("domain".to_string(), domain.to_lowercase());
+
+                detections.push(
+                    CredentialDetection::new(
+                        self,
+                        matched.as_str().to_string(),
+                        line.substring_box(matched.start(), matched.end()),
+                        line.score,
+                        0.9,
+                    )
+                    .with_metadata(metadata),
+                );
+            }
+        }
+
+        detections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(text: &str) -> Vec<String> {
+        EmailDetector::new()
+            .detect(&[TextLine::test_line(text)])
+            .into_iter()
+            .map(|detection| detection.text)
+            .collect()
+    }
+
+    #[test]
+    fn detects_addresses() {
+        assert_eq!(
+            detect("Contact: alice.smith+ops@Example.co.jp"),
+            ["alice.smith+ops@Example.co.jp"]
+        );
+        assert_eq!(
+            detect("from bob@example.com to carol_1@mail.example.org"),
+            ["bob@example.com", "carol_1@mail.example.org"]
+        );
+
+        let detections = EmailDetector::new().detect(&[TextLine::test_line("x alice@Example.COM")]);
+        assert_eq!(detections[0].metadata["domain"], "example.com");
+    }
+
+    #[test]
+    fn ignores_non_addresses() {
+        assert!(detect("git@github").is_empty());
+        assert!(detect("@alice mentioned this").is_empty());
+        assert!(detect("user@localhost").is_empty());
+        assert!(detect("image@2x.png").is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+//! IBAN detector implementation
+
+use crate::detectors::{CredentialDetectorLogic, PII_REMEDIATION_URL};
+use crate::ocr::TextLine;
+use crate::types::{CredentialDetection, DetectorCategory, Severity};
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// IBAN lengths by country code
+const IBAN_LENGTHS: &[(&str, usize)] = &[
+    ("AD", 24),
+    ("AE", 23),
+    ("AT", 20),
+    ("BA", 20),
+    ("BE", 16),
+    ("BG", 22),
+    ("BH", 22),
+    ("BR", 29),
+    ("CH", 21),
+    ("CY", 28),
+    ("CZ", 24),
+    ("DE", 22),
+    ("DK", 18),
+    ("EE", 20),
+    ("ES", 24),
+    ("FI", 18),
+    ("FO", 18),
+    ("FR", 27),
+    ("GB", 22),
+    ("GI", 23),
+    ("GL", 18),
+    ("GR", 27),
+    ("HR", 21),
+    ("HU", 28),
+    ("IE", 22),
+    ("IL", 23),
+    ("IS", 26),
+    ("IT", 27),
+    ("KW", 30),
+    ("KZ", 20),
+    ("LB", 28),
+    ("LI", 21),
+    ("LT", 20),
+    ("LU", 20),
+    ("LV", 21),
+    ("MC", 27),
+    ("ME", 22),
+    ("MT", 31),
+    ("NL", 18),
+    ("NO", 15),
+    ("PK", 24),
+    ("PL", 28),
+    ("PT", 25),
+    ("QA", 29),
+    ("RO", 24),
+    ("RS", 22),
+    ("SA", 24),
+    ("SE", 24),
+    ("SI", 19),
+    ("SK", 24),
+    ("SM", 27),
+    ("TR", 26),
+    ("UA", 29),
+];
+
+/// IBAN (International Bank Account Number) detector
+pub struct IbanDetector {
+    /// Regular expression representing IBANs, possibly grouped by 4 characters
+    pattern: Regex,
+}
+
+impl IbanDetector {
+    /// Create a new IBAN detector
+    pub fn new() -> Self {
+        let pattern =
+            Regex::new(r"\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]{4}){2,7}(?: ?[A-Z0-9]{1,3})?\b").unwrap();
+
+        Self { pattern }
+    }
+
+    /// Validate the check digits with the ISO 7064 mod-97 algorithm
+    fn mod97_valid(iban: &str) -> bool {
+        // Move the country code and check digits to the end, then map letters to 10..35
+        let rearranged = iban[4..].chars().chain(iban[..4].chars());
+
+        let mut remainder: u32 = 0;
+        for c in rearranged {
+            let Some(value) = c.to_digit(36) else {
+                return false;
+            };
+            remainder = if value < 10 {
+                (remainder * 10 + value) % 97
+            } else {
+                (remainder * 100 + value) % 97
+            };
+        }
+
+        remainder == 1
+    }
+}
+
+impl Default for IbanDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialDetectorLogic for IbanDetector {
+    fn name(&self) -> &str {
+        "IBAN Detector"
+    }
+
+    fn rule_id(&self) -> &str {
+        "pii-iban"
+    }
+
+    fn description(&self) -> &str {
+        "International bank account numbers"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn category(&self) -> DetectorCategory {
+        DetectorCategory::Pii
+    }
+
+    fn remediation_url(&self) -> Option<&str> {
+        Some(PII_REMEDIATION_URL)
+    }
+
+    fn detect(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
+        let mut detections = Vec::new();
+
+        for line in text_lines {
+            for matched in self.pattern.find_iter(&line.text) {
+                let country = &matched.as_str()[..2];
+                let Some(&(_, length)) = IBAN_LENGTHS.iter().find(|&&(code, _)| code == country)
+                else {
+                    continue;
+                };
+
+                // The pattern may run into following text, so cut it at the country's length
+                let mut iban = String::new();
+                let mut end = matched.start();
+                for (offset, c) in matched.as_str().char_indices() {
+                    if iban.len() == length {
+                        break;
+                    }
+                    if c != ' ' {
+                        iban.push(c);
+                        end = matched.start() + offset + c.len_utf8();
+                    }
+                }
+
+                // Only IBANs with the country's length and valid check digits are reported
+                if iban.len() != length || !Self::mod97_valid(&iban) {
+                    continue;
+                }
+
+                let mut metadata = BTreeMap::new();
+                metadata.insert("country".to_string(), country.to_string());
+
+                detections.push(
+                    CredentialDetection::new(
+                        self,
+                        line.text[matched.start()..end].to_string(),
+                        line.substring_box(matched.start(), end),
+                        line.score,
+                        0.95,
+                    )
+                    .with_metadata(metadata),
+                );
+            }
+        }
+
+        detections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(text: &str) -> Vec<CredentialDetection> {
+        IbanDetector::new().detect(&[TextLine::test_line(text)])
+    }
+
+    #[test]
+    fn mod97_check() {
+        assert!(IbanDetector::mod97_valid("GB82WEST12345698765432"));
+        assert!(IbanDetector::mod97_valid("DE89370400440532013000"));
+        assert!(!IbanDetector::mod97_valid("GB83WEST12345698765432"));
+        assert!(!IbanDetector::mod97_valid("GB82WEST1234569876543!"));
+    }
+
+    #[test]
+    fn detects_grouped_iban() {
+        let detections = detect("IBAN: GB82 WEST 1234 5698 7654 32 (GBP)");
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].text, "GB82 WEST 1234 5698 7654 32");
+        assert_eq!(detections[0].metadata["country"], "GB");
+    }
+
+    #[test]
+    fn cuts_iban_at_country_length() {
+        let detections = detect("DE89370400440532013000 ABCD");
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].text, "DE89370400440532013000");
+    }
+
+    #[test]
+    fn ignores_invalid_ibans() {
+        assert!(detect("GB83 WEST 1234 5698 7654 32").is_empty());
+        // Unknown country code
+        assert!(detect("XX82 WEST 1234 5698 7654 32").is_empty());
+    }
+}
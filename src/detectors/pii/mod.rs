@@ -0,0 +1,15 @@
+//! Personally identifiable information (PII) detector implementations
+
+mod email;
+mod iban;
+mod my_number;
+mod passport;
+mod phone;
+mod ssn;
+
+pub use email::EmailDetector;
+pub use iban::IbanDetector;
+pub use my_number::MyNumberDetector;
+pub use passport::PassportDetector;
+pub use phone::PhoneNumberDetector;
+pub use ssn::SsnDetector;
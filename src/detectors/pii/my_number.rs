@@ -0,0 +1,158 @@
+//! Japanese My Number (individual number) detector implementation
+
+use crate::detectors::{CredentialDetectorLogic, PII_REMEDIATION_URL};
+use crate::ocr::TextLine;
+use crate::types::{CredentialDetection, DetectorCategory, Severity};
+use regex::Regex;
+
+/// Japanese My Number detector
+pub struct MyNumberDetector {
+    /// Regular expression representing 12 digits, possibly grouped by 4
+    pattern: Regex,
+}
+
+impl MyNumberDetector {
+    /// Create a new My Number detector
+    pub fn new() -> Self {
+        let pattern = Regex::new(r"\b\d{4}[ \-]?\d{4}[ \-]?\d{4}\b").unwrap();
+
+        Self { pattern }
+    }
+
+    /// Determine if the match continues into adjacent space-separated alphanumeric groups
+    fn is_part_of_longer_number(text: &str, start: usize, end: usize) -> bool {
+        let before = text[..start].trim_end_matches([' ', '-']);
+        let after = text[end..].trim_start_matches([' ', '-']);
+
+        let continues_before = before.len() + 2 >= start
+            && before
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_ascii_alphanumeric());
+        let continues_after = after.len() + 2 >= text.len() - end
+            && after.chars().next().is_some_and(|c| c.is_ascii_digit());
+
+        continues_before || continues_after
+    }
+
+    /// Validate the check digit (the 12th digit)
+    fn check_digit_valid(digits: &[u32]) -> bool {
+        // Pn is the n-th digit counted from the right of the first 11 digits
+        let sum: u32 = digits[..11]
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, &p)| {
+                let n = i as u32 + 1;
+                let q = if n <= 6 { n + 1 } else { n - 5 };
+                p * q
+            })
+            .sum();
+
+        let remainder = sum % 11;
+        let expected = if remainder <= 1 { 0 } else { 11 - remainder };
+
+        digits[11] == expected
+    }
+}
+
+impl Default for MyNumberDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialDetectorLogic for MyNumberDetector {
+    fn name(&self) -> &str {
+        "My Number Detector"
+    }
+
+    fn rule_id(&self) -> &str {
+        "pii-my-number"
+    }
+
+    fn description(&self) -> &str {
+        "Japanese Individual Numbers (My Number)"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::High
+    }
+
+    fn category(&self) -> DetectorCategory {
+        DetectorCategory::Pii
+    }
+
+    fn remediation_url(&self) -> Option<&str> {
+        Some(PII_REMEDIATION_URL)
+    }
+
+    fn detect(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
+        let mut detections = Vec::new();
+
+        for line in text_lines {
+            for matched in self.pattern.find_iter(&line.text) {
+                let digits: Vec<u32> = matched
+                    .as_str()
+                    .chars()
+                    .filter_map(|c| c.to_digit(10))
+                    .collect();
+
+                // Skip groups that are part of a longer grouped number (e.g. an IBAN)
+                if Self::is_part_of_longer_number(&line.text, matched.start(), matched.end())
+                    || !Self::check_digit_valid(&digits)
+                {
+                    continue;
+                }
+
+                detections.push(CredentialDetection::new(
+                    self,
+                    matched.as_str().to_string(),
+                    line.substring_box(matched.start(), matched.end()),
+                    line.score,
+                    0.85,
+                ));
+            }
+        }
+
+        detections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(text: &str) -> Vec<CredentialDetection> {
+        MyNumberDetector::new().detect(&[TextLine::test_line(text)])
+    }
+
+    fn digits(number: &str) -> Vec<u32> {
+        number.chars().filter_map(|c| c.to_digit(10)).collect()
+    }
+
+    #[test]
+    fn check_digit() {
+        assert!(MyNumberDetector::check_digit_valid(&digits("123456789018")));
+        assert!(MyNumberDetector::check_digit_valid(&digits("000000000000")));
+        assert!(!MyNumberDetector::check_digit_valid(&digits(
+            "123456789012"
+        )));
+    }
+
+    #[test]
+    fn detects_grouped_numbers() {
+        let detections = detect("個人番号 1234 5678 9018");
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].text, "1234 5678 9018");
+
+        assert_eq!(detect("1234-5678-9018").len(), 1);
+    }
+
+    #[test]
+    fn ignores_groups_of_longer_numbers() {
+        assert!(detect("GB82 1234 5678 9018").is_empty());
+        assert!(detect("1234 5678 9018 7777").is_empty());
+        assert!(detect("1234 5678 9012").is_empty());
+    }
+}
@@ -0,0 +1,206 @@
+//! Passport number detector implementation
+
+use crate::detectors::{CredentialDetectorLogic, PII_REMEDIATION_URL};
+use crate::ocr::TextLine;
+use crate::types::{CredentialDetection, DetectorCategory, Severity};
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// Passport number detector
+///
+/// Detects passport-like ids next to passport keywords, and document numbers in
+/// machine readable zones (MRZ) whose check digit is valid.
+pub struct PassportDetector {
+    /// Passport-related keywords
+    keywords: Vec<&'static str>,
+    /// Regular expression representing passport-like ids (e.g. TK1234567)
+    id_pattern: Regex,
+    /// Regular expression representing the second line of a TD3 MRZ
+    mrz_pattern: Regex,
+}
+
+impl PassportDetector {
+    /// Create a new passport number detector
+    pub fn new() -> Self {
+        let keywords = vec!["passport", "旅券", "パスポート"];
+
+        let id_pattern = Regex::new(r"\b[A-Z]{1,2}[0-9]{6,8}\b").unwrap();
+
+        // Document number (9), check digit, nationality (3), birth date (6), check digit, ...
+        let mrz_pattern = Regex::new(r"([A-Z0-9<]{9})([0-9])[A-Z<]{3}[0-9]{6}[0-9][MFX<]").unwrap();
+
+        Self {
+            keywords,
+            id_pattern,
+            mrz_pattern,
+        }
+    }
+
+    /// Determine if text contains passport-related keywords
+    fn contains_passport_keyword(&self, text: &str) -> bool {
+        let text_lower = text.to_lowercase();
+        self.keywords
+            .iter()
+            .any(|&keyword| text_lower.contains(keyword))
+    }
+
+    /// Calculate an MRZ check digit (weights 7, 3, 1)
+    fn mrz_check_digit(field: &str) -> u32 {
+        field
+            .chars()
+            .zip([7, 3, 1].iter().cycle())
+            .map(|(c, weight)| {
+                let value = match c {
+                    '<' => 0,
+                    _ => c.to_digit(36).unwrap_or(0),
+                };
+                value * weight
+            })
+            .sum::<u32>()
+            % 10
+    }
+}
+
+impl Default for PassportDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialDetectorLogic for PassportDetector {
+    fn name(&self) -> &str {
+        "Passport Number Detector"
+    }
+
+    fn rule_id(&self) -> &str {
+        "pii-passport"
+    }
+
+    fn description(&self) -> &str {
+        "Passport numbers"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Medium
+    }
+
+    fn category(&self) -> DetectorCategory {
+        DetectorCategory::Pii
+    }
+
+    fn remediation_url(&self) -> Option<&str> {
+        Some(PII_REMEDIATION_URL)
+    }
+
+    fn detect(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
+        let mut detections = Vec::new();
+
+        // Passport-like ids are looked for on keyword lines and the line right after them.
+        // Each line is scanned once even when consecutive lines contain a keyword.
+        let mut near_keyword = vec![false; text_lines.len()];
+        for (i, line) in text_lines.iter().enumerate() {
+            if self.contains_passport_keyword(&line.text) {
+                near_keyword[i] = true;
+                if let Some(next) = near_keyword.get_mut(i + 1) {
+                    *next = true;
+                }
+            }
+        }
+
+        for (i, line) in text_lines.iter().enumerate() {
+            // Document numbers in machine readable zones
+            for capture in self.mrz_pattern.captures_iter(&line.text) {
+                let number = capture.get(1).unwrap();
+                let check_digit = capture[2].parse::<u32>().unwrap_or(10);
+                if Self::mrz_check_digit(number.as_str()) != check_digit {
+                    continue;
+                }
+
+                let trimmed = number.as_str().trim_end_matches('<');
+
+                let mut metadata = BTreeMap::new();
+                metadata.insert("source".to_string(), "mrz".to_string());
+
+                detections.push(
+                    CredentialDetection::new(
+                        self,
+                        trimmed.to_string(),
+                        line.substring_box(number.start(), number.start() + trimmed.len()),
+                        line.score,
+                        0.95,
+                    )
+                    .with_metadata(metadata),
+                );
+            }
+
+            // Passport-like ids near a keyword
+            if !near_keyword[i] {
+                continue;
+            }
+
+            for matched in self.id_pattern.find_iter(&line.text) {
+                let mut metadata = BTreeMap::new();
+                metadata.insert("source".to_string(), "keyword".to_string());
+
+                detections.push(
+                    CredentialDetection::new(
+                        self,
+                        matched.as_str().to_string(),
+                        line.substring_box(matched.start(), matched.end()),
+                        line.score,
+                        0.8,
+                    )
+                    .with_metadata(metadata),
+                );
+            }
+        }
+
+        detections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(texts: &[&str]) -> Vec<TextLine> {
+        texts.iter().map(|text| TextLine::test_line(text)).collect()
+    }
+
+    #[test]
+    fn mrz_check_digit() {
+        // ICAO 9303 specimen document number
+        assert_eq!(PassportDetector::mrz_check_digit("L898902C3"), 6);
+        assert_eq!(PassportDetector::mrz_check_digit("<<<<<<<<<"), 0);
+    }
+
+    #[test]
+    fn detects_mrz_document_number() {
+        let detections = PassportDetector::new().detect(&lines(&[
+            "P<UTOERIKSSON<<ANNA<MARIA<<<<<<<<<<<<<<<<<<<",
+            "L898902C36UTO7408122F1204159ZE184226B<<<<<10",
+        ]));
+
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].text, "L898902C3");
+        assert_eq!(detections[0].metadata["source"], "mrz");
+    }
+
+    #[test]
+    fn reports_each_number_once() {
+        let detections = PassportDetector::new().detect(&lines(&[
+            "Passport",
+            "Passport No. TK1234567",
+            "TK7654321",
+        ]));
+
+        let texts: Vec<&str> = detections.iter().map(|d| d.text.as_str()).collect();
+        assert_eq!(texts, ["TK1234567", "TK7654321"]);
+    }
+
+    #[test]
+    fn ignores_ids_far_from_keywords() {
+        let detections = PassportDetector::new().detect(&lines(&["Passport", "", "TK1234567"]));
+        assert!(detections.is_empty());
+    }
+}
@@ -0,0 +1,165 @@
+//! Phone number detector implementation
+
+use crate::detectors::{CredentialDetectorLogic, PII_REMEDIATION_URL};
+use crate::ocr::TextLine;
+use crate::types::{CredentialDetection, DetectorCategory, Severity};
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// Phone number detector
+pub struct PhoneNumberDetector {
+    /// Regular expressions representing phone number formats, paired with the format name
+    patterns: Vec<(&'static str, Regex)>,
+}
+
+impl PhoneNumberDetector {
+    /// Create a new phone number detector
+    pub fn new() -> Self {
+        let patterns = vec![
+            // International format (+81 90-1234-5678, +1 (415) 555-0100, +447911123456)
+            (
+                "international",
+                Regex::new(
+                    r"\+\d{1,3}[ \-.]?(?:\(\d{1,4}\)[ \-.]?)?\d{1,4}(?:[ \-.]?\d{2,4}){1,4}",
+                )
+                .unwrap(),
+            ),
+            // North American format ((415) 555-0100, 415-555-0100)
+            (
+                "north_american",
+                Regex::new(r"(?:\(\d{3}\)\s?|\b\d{3}[\-.])\d{3}[\-.]\d{4}\b").unwrap(),
+            ),
+            // Japanese domestic format (03-1234-5678, 090-1234-5678)
+            (
+                "japanese",
+                Regex::new(r"\b0\d{1,4}-\d{1,4}-\d{3,4}\b").unwrap(),
+            ),
+        ];
+
+        Self { patterns }
+    }
+}
+
+impl Default for PhoneNumberDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialDetectorLogic for PhoneNumberDetector {
+    fn name(&self) -> &str {
+        "Phone Number Detector"
+    }
+
+    fn rule_id(&self) -> &str {
+        "pii-phone-number"
+    }
+
+    fn description(&self) -> &str {
+        "Phone numbers"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Low
+    }
+
+    fn category(&self) -> DetectorCategory {
+        DetectorCategory::Pii
+    }
+
+    fn remediation_url(&self) -> Option<&str> {
+        Some(PII_REMEDIATION_URL)
+    }
+
+    fn detect(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
+        let mut detections = Vec::new();
+
+        for line in text_lines {
+            // Byte ranges already reported for this line, so formats do not report the same number twice
+            let mut reported: Vec<(usize, usize)> = Vec::new();
+
+            for (format, pattern) in &self.patterns {
+                for matched in pattern.find_iter(&line.text) {
+                    if reported
+                        .iter()
+                        .any(|&(start, end)| matched.start() < end && start < matched.end())
+                    {
+                        continue;
+                    }
+
+                    // E.164 numbers have at most 15 digits; shorter runs are usually dates or ids
+                    let digit_count = matched
+                        .as_str()
+                        .chars()
+                        .filter(char::is_ascii_digit)
+                        .count();
+                    if !(10..=15).contains(&digit_count) {
+                        continue;
+                    }
+
+                    reported.push((matched.start(), matched.end()));
+
+                    let mut metadata = BTreeMap::new();
+                    metadata.insert("format".to_string(), format.to_string());
+
+                    detections.push(
+                        CredentialDetection::new(
+                            self,
+                            matched.as_str().to_string(),
+                            line.substring_box(matched.start(), matched.end()),
+                            line.score,
+                            0.7,
+                        )
+                        .with_metadata(metadata),
+                    );
+                }
+            }
+        }
+
+        detections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(text: &str) -> Vec<(String, String)> {
+        PhoneNumberDetector::new()
+            .detect(&[TextLine::test_line(text)])
+            .into_iter()
+            .map(|detection| (detection.text, detection.metadata["format"].clone()))
+            .collect()
+    }
+
+    #[test]
+    fn detects_formats() {
+        assert_eq!(
+            detect("Tel: +81 90-1234-5678"),
+            [("+81 90-1234-5678".to_string(), "international".to_string())]
+        );
+        assert_eq!(
+            detect("Call (415) 555-0100 today"),
+            [("(415) 555-0100".to_string(), "north_american".to_string())]
+        );
+        assert_eq!(
+            detect("電話 090-1234-5678"),
+            [("090-1234-5678".to_string(), "japanese".to_string())]
+        );
+    }
+
+    #[test]
+    fn reports_each_number_once() {
+        // Also matches the North American format
+        assert_eq!(detect("+1 415-555-0100").len(), 1);
+    }
+
+    #[test]
+    fn ignores_versions_dates_and_addresses() {
+        assert!(detect("Version 10.0.19045.3803").is_empty());
+        assert!(detect("v1.2.3 released 2024-01-15").is_empty());
+        assert!(detect("2024.01.15 12:30:45").is_empty());
+        assert!(detect("Server 192.168.100.200").is_empty());
+        assert!(detect("Order 03-12-345").is_empty());
+    }
+}
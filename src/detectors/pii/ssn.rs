@@ -0,0 +1,124 @@
+//! US Social Security Number detector implementation
+
+use crate::detectors::{CredentialDetectorLogic, PII_REMEDIATION_URL};
+use crate::ocr::TextLine;
+use crate::types::{CredentialDetection, DetectorCategory, Severity};
+use regex::Regex;
+
+/// US Social Security Number detector
+pub struct SsnDetector {
+    /// Regular expression representing SSNs (AAA-GG-SSSS)
+    pattern: Regex,
+}
+
+impl SsnDetector {
+    /// Create a new SSN detector
+    pub fn new() -> Self {
+        // Separators are required; bare 9-digit runs are too ambiguous
+        let pattern = Regex::new(r"\b(\d{3})[\- ](\d{2})[\- ](\d{4})\b").unwrap();
+
+        Self { pattern }
+    }
+
+    /// Determine if the number can be issued (area, group and serial rules)
+    fn is_valid(area: &str, group: &str, serial: &str) -> bool {
+        let area: u32 = area.parse().unwrap_or(0);
+
+        area != 0 && area != 666 && area < 900 && group != "00" && serial != "0000"
+    }
+}
+
+impl Default for SsnDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialDetectorLogic for SsnDetector {
+    fn name(&self) -> &str {
+        "SSN Detector"
+    }
+
+    fn rule_id(&self) -> &str {
+        "pii-us-ssn"
+    }
+
+    fn description(&self) -> &str {
+        "US Social Security numbers"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::High
+    }
+
+    fn category(&self) -> DetectorCategory {
+        DetectorCategory::Pii
+    }
+
+    fn remediation_url(&self) -> Option<&str> {
+        Some(PII_REMEDIATION_URL)
+    }
+
+    fn detect(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
+        let mut detections = Vec::new();
+
+        for line in text_lines {
+            for capture in self.pattern.captures_iter(&line.text) {
+                if !Self::is_valid(&capture[1], &capture[2], &capture[3]) {
+                    continue;
+                }
+
+                let matched = capture.get(0).unwrap();
+
+                detections.push(CredentialDetection::new(
+                    self,
+                    matched.as_str().to_string(),
+                    line.substring_box(matched.start(), matched.end()),
+                    line.score,
+                    0.85,
+                ));
+            }
+        }
+
+        detections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(text: &str) -> Vec<String> {
+        SsnDetector::new()
+            .detect(&[TextLine::test_line(text)])
+            .into_iter()
+            .map(|detection| detection.text)
+            .collect()
+    }
+
+    #[test]
+    fn detects_valid_numbers() {
+        assert_eq!(detect("SSN: 123-45-6789"), ["123-45-6789"]);
+        assert_eq!(detect("SSN 899 12 3456"), ["899 12 3456"]);
+    }
+
+    #[test]
+    fn rejects_numbers_that_are_never_issued() {
+        for number in [
+            "000-12-3456",
+            "666-12-3456",
+            "900-12-3456",
+            "987-65-4321",
+            "123-00-4567",
+            "123-45-0000",
+        ] {
+            assert!(detect(number).is_empty(), "{}", number);
+        }
+    }
+
+    #[test]
+    fn requires_separators() {
+        assert!(detect("123456789").is_empty());
+        assert!(detect("1234-56-7890").is_empty());
+    }
+}
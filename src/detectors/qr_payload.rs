@@ -0,0 +1,264 @@
+//! QR code payload detector implementation
+
+use crate::detectors::{CredentialDetectorLogic, SECRET_REMEDIATION_URL};
+use crate::ocr::TextLine;
+use crate::types::{CredentialDetection, DetectorCategory, Severity};
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// QR code payload detector
+///
+/// Detects the secrets in `otpauth://` (TOTP/HOTP enrolment), `otpauth-migration://`
+/// (authenticator export) and `WIFI:` payloads, which usually come from decoded QR codes.
+pub struct QrPayloadDetector {
+    /// Regular expression representing otpauth URIs
+    otpauth_pattern: Regex,
+    /// Regular expression representing Wi-Fi network configurations
+    wifi_pattern: Regex,
+}
+
+impl QrPayloadDetector {
+    /// Create a new QR code payload detector
+    pub fn new() -> Self {
+        // Exports have no label: `otpauth-migration://offline?data=...`
+        let otpauth_pattern =
+            Regex::new(r"(?i)otpauth(-migration)?://([a-z]+)(?:/([^?\s]*))?\?(\S+)").unwrap();
+        let wifi_pattern = Regex::new(r"WIFI:((?:[^;\\]|\\.)*;)+;?").unwrap();
+
+        Self {
+            otpauth_pattern,
+            wifi_pattern,
+        }
+    }
+
+    /// Decode `%XX` escapes in a URI component
+    fn percent_decode(value: &str) -> String {
+        let bytes = value.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let escaped = (bytes[i] == b'%')
+                .then(|| value.get(i + 1..i + 3))
+                .flatten()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+            match escaped {
+                Some(byte) => {
+                    decoded.push(byte);
+                    i += 3;
+                }
+                None => {
+                    decoded.push(if bytes[i] == b'+' { b' ' } else { bytes[i] });
+                    i += 1;
+                }
+            }
+        }
+
+        String::from_utf8_lossy(&decoded).into_owned()
+    }
+
+    /// Split a `WIFI:` payload into its fields (`T`, `S`, `P`, `H`), unescaping `\;` etc.
+    fn wifi_fields(payload: &str) -> BTreeMap<String, String> {
+        let mut fields = BTreeMap::new();
+        let mut current = String::new();
+        let mut chars = payload.trim_start_matches("WIFI:").chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => current.extend(chars.next()),
+                ';' => {
+                    if let Some((key, value)) = current.split_once(':') {
+                        fields.insert(key.to_string(), value.to_string());
+                    }
+                    current.clear();
+                }
+                _ => current.push(c),
+            }
+        }
+
+        fields
+    }
+
+    /// Detect the secrets of otpauth URIs in a text line
+    fn detect_otpauth(&self, line: &TextLine, detections: &mut Vec<CredentialDetection>) {
+        for capture in self.otpauth_pattern.captures_iter(&line.text) {
+            let migration = capture.get(1).is_some();
+            let query: BTreeMap<String, String> = capture[4]
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .map(|(key, value)| (key.to_lowercase(), Self::percent_decode(value)))
+                .collect();
+
+            // Exports carry every account in the `data` parameter
+            let secret_key = if migration { "data" } else { "secret" };
+            let Some(secret) = query.get(secret_key) else {
+                continue;
+            };
+
+            let mut metadata = BTreeMap::new();
+            if migration {
+                metadata.insert("type".to_string(), "otpauth-migration".to_string());
+            } else {
+                metadata.insert("type".to_string(), capture[2].to_lowercase());
+                let label = capture.get(3).map_or("", |label| label.as_str());
+                metadata.insert("label".to_string(), Self::percent_decode(label));
+                for key in ["issuer", "algorithm", "digits", "period"] {
+                    if let Some(value) = query.get(key) {
+                        metadata.insert(key.to_string(), value.clone());
+                    }
+                }
+            }
+
+            let matched = capture.get(0).unwrap();
+
+            detections.push(
+                CredentialDetection::new(
+                    self,
+                    secret.clone(),
+                    line.substring_box(matched.start(), matched.end()),
+                    line.score,
+                    0.99,
+                )
+                .with_metadata(metadata),
+            );
+        }
+    }
+
+    /// Detect the passwords of Wi-Fi network configurations in a text line
+    fn detect_wifi(&self, line: &TextLine, detections: &mut Vec<CredentialDetection>) {
+        for matched in self.wifi_pattern.find_iter(&line.text) {
+            let fields = Self::wifi_fields(matched.as_str());
+
+            // Open networks have no password
+            let Some(password) = fields.get("P").filter(|password| !password.is_empty()) else {
+                continue;
+            };
+
+            let mut metadata = BTreeMap::new();
+            metadata.insert("type".to_string(), "wifi".to_string());
+            if let Some(ssid) = fields.get("S") {
+                metadata.insert("ssid".to_string(), ssid.clone());
+            }
+            if let Some(security) = fields.get("T") {
+                metadata.insert("security".to_string(), security.clone());
+            }
+
+            detections.push(
+                CredentialDetection::new(
+                    self,
+                    password.clone(),
+                    line.substring_box(matched.start(), matched.end()),
+                    line.score,
+                    0.95,
+                )
+                .with_metadata(metadata),
+            );
+        }
+    }
+}
+
+impl Default for QrPayloadDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialDetectorLogic for QrPayloadDetector {
+    fn name(&self) -> &str {
+        "QR Payload Detector"
+    }
+
+    fn rule_id(&self) -> &str {
+        "qr-payload"
+    }
+
+    fn description(&self) -> &str {
+        "OTP enrolment secrets and Wi-Fi passwords in QR code payloads"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::High
+    }
+
+    fn category(&self) -> DetectorCategory {
+        DetectorCategory::Token
+    }
+
+    fn remediation_url(&self) -> Option<&str> {
+        Some(SECRET_REMEDIATION_URL)
+    }
+
+    fn detect(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
+        let mut detections = Vec::new();
+
+        for line in text_lines {
+            self.detect_otpauth(line, &mut detections);
+            self.detect_wifi(line, &mut detections);
+        }
+
+        detections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(text: &str) -> Vec<CredentialDetection> {
+        QrPayloadDetector::new().detect(&[TextLine::test_line(text)])
+    }
+
+    #[test]
+    fn detects_otpauth_secrets() {
+        let detections = detect(
+            "otpauth://totp/Example%20Co:alice%40example.com?secret=JBSWY3DPEHPK3PXP&issuer=Example%20Co&digits=6",
+        );
+
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].text, "JBSWY3DPEHPK3PXP");
+        assert_eq!(detections[0].credential_score, 0.99);
+        let metadata = &detections[0].metadata;
+        assert_eq!(metadata["type"], "totp");
+        assert_eq!(metadata["label"], "Example Co:alice@example.com");
+        assert_eq!(metadata["issuer"], "Example Co");
+        assert_eq!(metadata["digits"], "6");
+    }
+
+    #[test]
+    fn detects_authenticator_exports() {
+        let detections = detect("otpauth-migration://offline?data=CjEKCkhlbGxvId6tvu8%3D");
+
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].text, "CjEKCkhlbGxvId6tvu8=");
+        assert_eq!(detections[0].metadata["type"], "otpauth-migration");
+        assert!(!detections[0].metadata.contains_key("label"));
+    }
+
+    #[test]
+    fn ignores_otpauth_without_secret() {
+        assert!(detect("otpauth://totp/alice?issuer=Example").is_empty());
+    }
+
+    #[test]
+    fn detects_wifi_passwords() {
+        let detections = detect(r"WIFI:T:WPA;S:Office\;5G;P:pa\;ss\:word;H:false;;");
+
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].text, "pa;ss:word");
+        assert_eq!(detections[0].metadata["ssid"], "Office;5G");
+        assert_eq!(detections[0].metadata["security"], "WPA");
+    }
+
+    #[test]
+    fn ignores_open_wifi_networks() {
+        assert!(detect("WIFI:T:nopass;S:Guest;P:;;").is_empty());
+        assert!(detect("WIFI:S:Cafe;;").is_empty());
+    }
+
+    #[test]
+    fn percent_decodes_components() {
+        assert_eq!(QrPayloadDetector::percent_decode("a%20b+c%2"), "a b c%2");
+        assert_eq!(QrPayloadDetector::percent_decode("%E3%81%82"), "あ");
+    }
+}
@@ -0,0 +1,275 @@
+//! Cryptocurrency wallet seed phrase detector implementation
+
+use crate::detectors::CredentialDetectorLogic;
+use crate::ocr::TextLine;
+use crate::types::{BoundingBox, CredentialDetection, DetectorCategory, Severity};
+use bip39::{Language, Mnemonic};
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// Numbers of words in the recovery phrases looked for, longest first
+const WORD_COUNTS: [usize; 3] = [24, 18, 12];
+
+/// A BIP-39 word found in the text lines
+struct WordToken {
+    /// Lowercased word
+    word: String,
+    /// Index of the text line containing the word
+    line_index: usize,
+    /// Byte range of the word in the text line
+    start: usize,
+    end: usize,
+}
+
+/// BIP-39 recovery phrase detector
+///
+/// Recognises runs of 12, 18 or 24 wordlist words, which may span several text lines
+/// and be numbered (`1. abandon 2. ability ...`), and validates the checksum.
+pub struct SeedPhraseDetector {
+    /// Regular expression representing a single word
+    word_pattern: Regex,
+    /// Regular expression representing word numbering and separators between words
+    separator_pattern: Regex,
+}
+
+impl SeedPhraseDetector {
+    /// Create a new seed phrase detector
+    pub fn new() -> Self {
+        let word_pattern = Regex::new(r"[A-Za-z]+").unwrap();
+        let separator_pattern = Regex::new(r"^[\s\d.,:;)(\-#]*$").unwrap();
+
+        Self {
+            word_pattern,
+            separator_pattern,
+        }
+    }
+
+    /// Split the text lines into runs of consecutive BIP-39 words
+    fn word_runs(&self, text_lines: &[TextLine]) -> Vec<Vec<WordToken>> {
+        let mut runs = Vec::new();
+        let mut current: Vec<WordToken> = Vec::new();
+
+        for (line_index, line) in text_lines.iter().enumerate() {
+            let mut previous_end = 0;
+
+            for matched in self.word_pattern.find_iter(&line.text) {
+                let word = matched.as_str().to_lowercase();
+                let separated = self
+                    .separator_pattern
+                    .is_match(&line.text[previous_end..matched.start()]);
+                previous_end = matched.end();
+
+                // Unknown words, or anything other than numbering between words, end the run
+                let known = Language::English.find_word(&word).is_some();
+                if !separated || !known {
+                    runs.push(std::mem::take(&mut current));
+                }
+                if !known {
+                    continue;
+                }
+
+                current.push(WordToken {
+                    word,
+                    line_index,
+                    start: matched.start(),
+                    end: matched.end(),
+                });
+            }
+
+            if !self.separator_pattern.is_match(&line.text[previous_end..]) {
+                runs.push(std::mem::take(&mut current));
+            }
+        }
+        runs.push(current);
+
+        runs.retain(|run| run.len() >= WORD_COUNTS[WORD_COUNTS.len() - 1]);
+        runs
+    }
+
+    /// Bounding box covering the words, which may span several lines
+    fn words_box(text_lines: &[TextLine], words: &[WordToken]) -> BoundingBox {
+        words
+            .iter()
+            .map(|token| text_lines[token.line_index].substring_box(token.start, token.end))
+            .reduce(|acc, bounding_box| acc.union(&bounding_box))
+            .unwrap()
+    }
+
+    /// Mean OCR confidence of the lines containing the words
+    fn words_score(text_lines: &[TextLine], words: &[WordToken]) -> f32 {
+        let total: f32 = words
+            .iter()
+            .map(|token| text_lines[token.line_index].score)
+            .sum();
+        total / words.len() as f32
+    }
+
+    /// Find the phrases with a valid checksum in a run of words
+    fn valid_phrases(run: &[WordToken]) -> Vec<&[WordToken]> {
+        let mut phrases = Vec::new();
+        let mut start = 0;
+
+        'outer: while start < run.len() {
+            for count in WORD_COUNTS {
+                let Some(window) = run.get(start..start + count) else {
+                    continue;
+                };
+
+                let phrase = window
+                    .iter()
+                    .map(|token| token.word.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                if Mnemonic::parse_in_normalized(Language::English, &phrase).is_ok() {
+                    phrases.push(window);
+                    start += count;
+                    continue 'outer;
+                }
+            }
+            start += 1;
+        }
+
+        phrases
+    }
+}
+
+impl Default for SeedPhraseDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialDetectorLogic for SeedPhraseDetector {
+    fn name(&self) -> &str {
+        "Seed Phrase Detector"
+    }
+
+    fn rule_id(&self) -> &str {
+        "seed-phrase"
+    }
+
+    fn description(&self) -> &str {
+        "BIP-39 wallet recovery phrases"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Critical
+    }
+
+    fn category(&self) -> DetectorCategory {
+        DetectorCategory::PrivateKey
+    }
+
+    fn detect(&self, text_lines: &[TextLine]) -> Vec<CredentialDetection> {
+        let mut detections = Vec::new();
+
+        for run in self.word_runs(text_lines) {
+            let mut phrases: Vec<(&[WordToken], bool)> = Self::valid_phrases(&run)
+                .into_iter()
+                .map(|phrase| (phrase, true))
+                .collect();
+
+            // OCR errors break the checksum, but a long run of wordlist words is still suspicious
+            if phrases.is_empty() {
+                let count = WORD_COUNTS
+                    .into_iter()
+                    .find(|&count| count <= run.len())
+                    .unwrap();
+                phrases.push((&run[..count], false));
+            }
+
+            for (phrase, checksum_valid) in phrases {
+                let text = phrase
+                    .iter()
+                    .map(|token| token.word.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                let mut metadata = BTreeMap::new();
+                metadata.insert("word_count".to_string(), phrase.len().to_string());
+                metadata.insert(
+                    "checksum".to_string(),
+                    if checksum_valid { "valid" } else { "invalid" }.to_string(),
+                );
+
+                detections.push(
+                    CredentialDetection::new(
+                        self,
+                        text,
+                        Self::words_box(text_lines, phrase),
+                        Self::words_score(text_lines, phrase),
+                        if checksum_valid { 0.99 } else { 0.8 },
+                    )
+                    .with_metadata(metadata),
+                );
+            }
+        }
+
+        detections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(texts: &[&str]) -> Vec<TextLine> {
+        texts.iter().map(|text| TextLine::test_line(text)).collect()
+    }
+
+    /// BIP-39 test vector with a valid checksum
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn detects_phrase_with_valid_checksum() {
+        let detections = SeedPhraseDetector::new().detect(&lines(&[PHRASE]));
+
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].text, PHRASE);
+        assert_eq!(detections[0].metadata["word_count"], "12");
+        assert_eq!(detections[0].metadata["checksum"], "valid");
+        assert_eq!(detections[0].credential_score, 0.99);
+    }
+
+    #[test]
+    fn invalid_checksum_scores_lower() {
+        let phrase = ["abandon"; 12].join(" ");
+        let detections = SeedPhraseDetector::new().detect(&lines(&[&phrase]));
+
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].metadata["checksum"], "invalid");
+        assert_eq!(detections[0].credential_score, 0.8);
+    }
+
+    #[test]
+    fn detects_24_word_phrase() {
+        let phrase = format!("{} art", ["abandon"; 23].join(" "));
+        let detections = SeedPhraseDetector::new().detect(&lines(&[&phrase]));
+
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].metadata["word_count"], "24");
+        assert_eq!(detections[0].metadata["checksum"], "valid");
+    }
+
+    #[test]
+    fn detects_numbered_phrase_across_lines() {
+        let detections = SeedPhraseDetector::new().detect(&lines(&[
+            "1. abandon 2. abandon 3. abandon 4. abandon",
+            "5. abandon 6. abandon 7. abandon 8. abandon",
+            "9. abandon 10. abandon 11. abandon 12. about",
+        ]));
+
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].text, PHRASE);
+        assert_eq!(detections[0].metadata["checksum"], "valid");
+    }
+
+    #[test]
+    fn ignores_prose() {
+        let detections = SeedPhraseDetector::new().detect(&lines(&[
+            "The quick brown fox jumps over the lazy dog and then runs away into the forest",
+        ]));
+
+        assert!(detections.is_empty());
+    }
+}
@@ -17,6 +17,34 @@ pub enum CredentialDetectorError {
     #[error("OCR processing failed: {0}")]
     OcrProcessError(String),
 
+    /// Error when pre-computed OCR results cannot be parsed
+    #[error("OCR result import failed: {0}")]
+    OcrImportError(String),
+
+    /// Error when a PDF document cannot be parsed
+    #[error("PDF loading failed: {0}")]
+    PdfLoadError(String),
+
+    /// Error when a video cannot be demultiplexed or decoded
+    #[error("Video decoding failed: {0}")]
+    VideoLoadError(String),
+
+    /// Error when an archive cannot be read or exceeds the extraction limits
+    #[error("Archive extraction failed: {0}")]
+    ArchiveError(String),
+
+    /// Error when a git repository cannot be opened or read
+    #[error("Git repository access failed: {0}")]
+    GitError(String),
+
+    /// Error when an email message cannot be parsed
+    #[error("Email parsing failed: {0}")]
+    EmailParseError(String),
+
+    /// Error when the HTTP server cannot be started
+    #[error("HTTP server failed: {0}")]
+    ServerError(String),
+
     /// Error when file is not found
     #[error("File not found: {0}")]
     FileNotFoundError(String),
@@ -0,0 +1,115 @@
+//! ALTO XML import
+
+use crate::error::CredentialDetectorError;
+use crate::import::parse_xml;
+use crate::ocr::TextLine;
+use roxmltree::Node;
+
+/// Read a numeric attribute of an ALTO element
+fn number(node: &Node, name: &str) -> f32 {
+    node.attribute(name)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0.0)
+}
+
+/// Parse an ALTO XML document (any version) into text lines
+///
+/// Coordinates are in the document's measurement unit, which is pixels for most OCR
+/// engines. Lines are scored with the mean word confidence (`WC`), or 1.0 when absent.
+pub fn parse_alto(alto: &str) -> Result<Vec<TextLine>, CredentialDetectorError> {
+    let document = parse_xml(alto)?;
+    let mut text_lines = Vec::new();
+
+    // Element names are matched without namespace, as it differs between ALTO versions
+    for line in document
+        .descendants()
+        .filter(|n| n.tag_name().name() == "TextLine")
+    {
+        let mut text = String::new();
+        let mut confidences = Vec::new();
+        let mut after_string = false;
+
+        for child in line.children().filter(|n| n.is_element()) {
+            match child.tag_name().name() {
+                "String" => {
+                    // Some writers omit SP elements between strings
+                    if after_string {
+                        text.push(' ');
+                    }
+                    text.push_str(child.attribute("CONTENT").unwrap_or_default());
+                    if let Some(confidence) = child.attribute("WC").and_then(|v| v.parse().ok()) {
+                        confidences.push(confidence);
+                    }
+                    after_string = true;
+                    continue;
+                }
+                "SP" => text.push(' '),
+                // Hyphen at the end of the line
+                "HYP" => text.push_str(child.attribute("CONTENT").unwrap_or("-")),
+                _ => {}
+            }
+            after_string = false;
+        }
+
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            continue;
+        }
+
+        let score = if confidences.is_empty() {
+            1.0
+        } else {
+            confidences.iter().sum::<f32>() / confidences.len() as f32
+        };
+
+        text_lines.push(TextLine {
+            text,
+            x: number(&line, "HPOS"),
+            y: number(&line, "VPOS"),
+            width: number(&line, "WIDTH"),
+            height: number(&line, "HEIGHT"),
+            score,
+        });
+    }
+
+    Ok(text_lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_alto() {
+        let alto = r#"<?xml version="1.0" encoding="UTF-8"?>
+<alto xmlns="http://www.loc.gov/standards/alto/ns-v4#">
+ <Layout><Page><PrintSpace><TextBlock>
+  <TextLine HPOS="10" VPOS="20" WIDTH="300" HEIGHT="20">
+   <String CONTENT="token:" WC="0.9"/><SP/><String CONTENT="ghp_abc" WC="0.7"/>
+  </TextLine>
+  <TextLine HPOS="10" VPOS="50" WIDTH="100" HEIGHT="20">
+   <String CONTENT="pass"/><String CONTENT="word"/><HYP CONTENT="-"/>
+  </TextLine>
+  <TextLine HPOS="0" VPOS="0" WIDTH="1" HEIGHT="1"><SP/></TextLine>
+ </TextBlock></PrintSpace></Page></Layout>
+</alto>"#;
+
+        let lines = parse_alto(alto).unwrap();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].text, "token: ghp_abc");
+        assert_eq!(
+            (lines[0].x, lines[0].y, lines[0].width, lines[0].height),
+            (10.0, 20.0, 300.0, 20.0)
+        );
+        assert!((lines[0].score - 0.8).abs() < 1e-6);
+        // Strings without SP elements are still separated
+        assert_eq!(lines[1].text, "pass word-");
+        assert_eq!(lines[1].score, 1.0);
+    }
+
+    #[test]
+    fn rejects_malformed_documents() {
+        assert!(parse_alto("<alto><TextLine></alto>").is_err());
+    }
+}
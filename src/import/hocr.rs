@@ -0,0 +1,267 @@
+//! hOCR import
+
+use crate::error::CredentialDetectorError;
+use crate::import::parse_xml;
+use crate::ocr::TextLine;
+use regex::{Captures, Regex};
+use roxmltree::{Document, Node};
+use std::sync::OnceLock;
+
+/// hOCR classes of line-level elements
+const LINE_CLASSES: [&str; 5] = [
+    "ocr_line",
+    "ocrx_line",
+    "ocr_header",
+    "ocr_caption",
+    "ocr_textfloat",
+];
+
+/// HTML elements without an end tag
+const VOID_ELEMENTS: &str = "area|base|br|col|embed|hr|img|input|link|meta|param|source|track|wbr";
+
+/// HTML named character references that are not predefined in XML
+const HTML_ENTITIES: [(&str, char); 28] = [
+    ("nbsp", '\u{a0}'),
+    ("ensp", '\u{2002}'),
+    ("emsp", '\u{2003}'),
+    ("thinsp", '\u{2009}'),
+    ("shy", '\u{ad}'),
+    ("copy", '©'),
+    ("reg", '®'),
+    ("trade", '™'),
+    ("hellip", '…'),
+    ("mdash", '—'),
+    ("ndash", '–'),
+    ("lsquo", '‘'),
+    ("rsquo", '’'),
+    ("ldquo", '“'),
+    ("rdquo", '”'),
+    ("laquo", '«'),
+    ("raquo", '»'),
+    ("middot", '·'),
+    ("bull", '•'),
+    ("deg", '°'),
+    ("times", '×'),
+    ("divide", '÷'),
+    ("euro", '€'),
+    ("pound", '£'),
+    ("yen", '¥'),
+    ("cent", '¢'),
+    ("sect", '§'),
+    ("para", '¶'),
+];
+
+/// Regular expression representing unclosed void element tags such as `<meta ...>`
+fn void_element_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(&format!(r"(?i)<((?:{VOID_ELEMENTS})\b[^>]*?)/?>")).unwrap())
+}
+
+/// Regular expression representing `&` and the character reference it may start
+fn entity_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"&(#[0-9]+;|#[xX][0-9A-Fa-f]+;|[A-Za-z][A-Za-z0-9]*;)?").unwrap()
+    })
+}
+
+/// Turn an HTML hOCR document into well-formed XML
+///
+/// Tesseract writes XHTML, but other tools and hand-edited files use HTML, with
+/// unclosed `<meta>` tags and entities such as `&nbsp;`.
+fn html_to_xml(html: &str) -> String {
+    let closed = void_element_pattern().replace_all(html, "<$1/>");
+
+    entity_pattern()
+        .replace_all(&closed, |capture: &Captures| {
+            let Some(reference) = capture.get(1) else {
+                // A bare `&`
+                return "&amp;".to_string();
+            };
+            let name = reference.as_str().trim_end_matches(';');
+            if name.starts_with('#') || ["amp", "lt", "gt", "quot", "apos"].contains(&name) {
+                return capture[0].to_string();
+            }
+            match HTML_ENTITIES.iter().find(|(entity, _)| *entity == name) {
+                Some((_, c)) => c.to_string(),
+                None => format!("&amp;{}", reference.as_str()),
+            }
+        })
+        .into_owned()
+}
+
+/// Determine if an element has the given hOCR class
+fn has_class(node: &Node, classes: &[&str]) -> bool {
+    node.attribute("class")
+        .is_some_and(|value| value.split_whitespace().any(|c| classes.contains(&c)))
+}
+
+/// Read a property (e.g. `bbox`, `x_wconf`) from an hOCR `title` attribute
+fn title_property<'a>(node: &Node<'a, '_>, name: &str) -> Option<Vec<&'a str>> {
+    node.attribute("title")?
+        .split(';')
+        .map(|property| property.split_whitespace().collect::<Vec<_>>())
+        .find(|values| values.first() == Some(&name))
+        .map(|values| values[1..].to_vec())
+}
+
+/// Concatenated text content of an element
+fn text_content(node: &Node) -> String {
+    node.descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect()
+}
+
+/// Parse an hOCR document (e.g. `tesseract image out hocr`) into text lines
+///
+/// Lines take their geometry from the `bbox` property and their score from the mean
+/// `x_wconf` of their words, scaled to 0.0-1.0 (1.0 when absent).
+pub fn parse_hocr(hocr: &str) -> Result<Vec<TextLine>, CredentialDetectorError> {
+    match parse_xml(hocr) {
+        Ok(document) => Ok(document_lines(&document)),
+        // Retry HTML hOCR as XML, reporting the original error if that fails too
+        Err(error) => {
+            let xml = html_to_xml(hocr);
+            let document = parse_xml(&xml).map_err(|_| error)?;
+            Ok(document_lines(&document))
+        }
+    }
+}
+
+/// Collect the text lines of a parsed hOCR document
+fn document_lines(document: &Document) -> Vec<TextLine> {
+    let mut text_lines = Vec::new();
+
+    for line in document
+        .descendants()
+        .filter(|n| n.is_element() && has_class(n, &LINE_CLASSES))
+    {
+        let Some(bbox) = title_property(&line, "bbox") else {
+            continue;
+        };
+        let [x0, y0, x1, y1] = match bbox[..] {
+            [x0, y0, x1, y1] => [x0, y0, x1, y1].map(|v| v.parse::<f32>().unwrap_or(0.0)),
+            _ => continue,
+        };
+
+        let words: Vec<Node> = line
+            .descendants()
+            .filter(|n| n.is_element() && has_class(n, &["ocrx_word"]))
+            .collect();
+
+        let text = if words.is_empty() {
+            text_content(&line)
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        } else {
+            words
+                .iter()
+                .map(|word| text_content(word).trim().to_string())
+                .filter(|word| !word.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        if text.is_empty() {
+            continue;
+        }
+
+        let confidences: Vec<f32> = words
+            .iter()
+            .filter_map(|word| title_property(word, "x_wconf")?.first()?.parse().ok())
+            .collect();
+        let score = if confidences.is_empty() {
+            1.0
+        } else {
+            confidences.iter().sum::<f32>() / confidences.len() as f32 / 100.0
+        };
+
+        text_lines.push(TextLine {
+            text,
+            x: x0,
+            y: y0,
+            width: x1 - x0,
+            height: y1 - y0,
+            score,
+        });
+    }
+
+    text_lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XHTML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Transitional//EN"
+    "http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd">
+<html xmlns="http://www.w3.org/1999/xhtml">
+ <head><meta name="ocr-system" content="tesseract" /></head>
+ <body>
+  <div class="ocr_page" title="bbox 0 0 800 600">
+   <span class="ocr_line" title="bbox 10 20 310 40; baseline 0 -5">
+    <span class="ocrx_word" title="bbox 10 20 100 40; x_wconf 90">API_KEY</span>
+    <span class="ocrx_word" title="bbox 110 20 310 40; x_wconf 70">abc&amp;123</span>
+   </span>
+   <span class="ocr_caption" title="bbox 10 50 100 60">Figure  1</span>
+   <span class="ocr_line" title="bbox 0 0 1 1"><span class="ocrx_word"> </span></span>
+  </div>
+ </body>
+</html>"#;
+
+    #[test]
+    fn parses_xhtml_hocr() {
+        let lines = parse_hocr(XHTML).unwrap();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].text, "API_KEY abc&123");
+        assert_eq!(
+            (lines[0].x, lines[0].y, lines[0].width, lines[0].height),
+            (10.0, 20.0, 300.0, 20.0)
+        );
+        assert!((lines[0].score - 0.8).abs() < 1e-6);
+        // Lines without words use their text content and full confidence
+        assert_eq!(lines[1].text, "Figure 1");
+        assert_eq!(lines[1].score, 1.0);
+    }
+
+    #[test]
+    fn parses_html_hocr() {
+        let html = r#"<!DOCTYPE html>
+<html>
+ <head><meta charset="utf-8"><meta name="ocr-system" content="other"><br></head>
+ <body>
+  <p class="ocr_par">
+   <span class='ocr_line' title="bbox 0 0 200 20">password:&nbsp;hunter2 &copy; R&D &unknown;</span>
+  </p>
+ </body>
+</html>"#;
+
+        let lines = parse_hocr(html).unwrap();
+
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].text, "password: hunter2 © R&D &unknown;");
+    }
+
+    #[test]
+    fn rejects_malformed_documents() {
+        assert!(matches!(
+            parse_hocr("<html><body><span class=\"ocr_line\"></body>"),
+            Err(CredentialDetectorError::OcrImportError(_))
+        ));
+    }
+
+    #[test]
+    fn converts_html_to_xml() {
+        assert_eq!(
+            html_to_xml(r#"<META charset="utf-8"><br/><img src="a.png" />"#),
+            r#"<META charset="utf-8"/><br/><img src="a.png" />"#
+        );
+        assert_eq!(
+            html_to_xml("&lt;&#65;&#x42;&nbsp;&amp; & &bogus;"),
+            "&lt;&#65;&#x42;\u{a0}&amp; &amp; &amp;bogus;"
+        );
+    }
+}
@@ -0,0 +1,26 @@
+//! Import of OCR results produced by other tools
+//!
+//! Pipelines that already OCR images upstream can feed their results straight into
+//! the credential detectors, e.g. through [`crate::PrecomputedText`] or
+//! [`crate::CredentialDetector::detect_from_text_lines`].
+
+mod alto;
+mod hocr;
+mod tsv;
+
+pub use alto::parse_alto;
+pub use hocr::parse_hocr;
+pub use tsv::parse_tesseract_tsv;
+
+use crate::error::CredentialDetectorError;
+
+/// Parse an XML document, allowing the DTDs that hOCR and ALTO files often declare
+fn parse_xml(xml: &str) -> Result<roxmltree::Document<'_>, CredentialDetectorError> {
+    let options = roxmltree::ParsingOptions {
+        allow_dtd: true,
+        ..Default::default()
+    };
+
+    roxmltree::Document::parse_with_options(xml, options)
+        .map_err(|e| CredentialDetectorError::OcrImportError(e.to_string()))
+}
@@ -0,0 +1,170 @@
+//! Tesseract TSV import
+
+use crate::error::CredentialDetectorError;
+use crate::ocr::TextLine;
+use std::collections::HashMap;
+
+/// Columns of the Tesseract TSV format used to build text lines
+const COLUMNS: [&str; 11] = [
+    "level",
+    "page_num",
+    "block_num",
+    "par_num",
+    "line_num",
+    "left",
+    "top",
+    "width",
+    "height",
+    "conf",
+    "text",
+];
+
+/// Parse Tesseract TSV output (`tesseract image out tsv`) into text lines
+///
+/// Words (level 5) are grouped by page, block, paragraph and line number. The line
+/// score is the mean word confidence scaled to 0.0-1.0.
+pub fn parse_tesseract_tsv(tsv: &str) -> Result<Vec<TextLine>, CredentialDetectorError> {
+    let mut rows = tsv.lines();
+
+    // Locate the columns by name rather than position
+    let header: Vec<&str> = rows.next().unwrap_or_default().split('\t').collect();
+    let mut indices = [0; COLUMNS.len()];
+    for (index, column) in indices.iter_mut().zip(COLUMNS) {
+        *index = match header.iter().position(|&name| name.trim() == column) {
+            Some(position) => position,
+            None => {
+                return Err(CredentialDetectorError::OcrImportError(format!(
+                    "Missing TSV column: {}",
+                    column
+                )));
+            }
+        };
+    }
+    let [
+        level,
+        page,
+        block,
+        paragraph,
+        line_number,
+        left,
+        top,
+        width,
+        height,
+        conf,
+        text,
+    ] = indices;
+
+    let mut lines: Vec<(TextLine, f32, usize)> = Vec::new();
+    let mut line_indices: HashMap<[&str; 4], usize> = HashMap::new();
+
+    for row in rows {
+        let fields: Vec<&str> = row.split('\t').collect();
+        if fields.len() < header.len() || fields[level] != "5" {
+            continue;
+        }
+
+        let word = fields[text].trim();
+        let confidence: f32 = fields[conf].parse().unwrap_or(-1.0);
+        if word.is_empty() || confidence < 0.0 {
+            continue;
+        }
+
+        let [x, y, w, h] =
+            [left, top, width, height].map(|i| fields[i].parse::<f32>().unwrap_or(0.0));
+
+        let key = [
+            fields[page],
+            fields[block],
+            fields[paragraph],
+            fields[line_number],
+        ];
+        match line_indices.get(&key) {
+            Some(&index) => {
+                let (line, total_confidence, words) = &mut lines[index];
+                let right = (line.x + line.width).max(x + w);
+                let bottom = (line.y + line.height).max(y + h);
+                line.x = line.x.min(x);
+                line.y = line.y.min(y);
+                line.width = right - line.x;
+                line.height = bottom - line.y;
+                line.text.push(' ');
+                line.text.push_str(word);
+                *total_confidence += confidence;
+                *words += 1;
+            }
+            None => {
+                line_indices.insert(key, lines.len());
+                lines.push((
+                    TextLine {
+                        text: word.to_string(),
+                        x,
+                        y,
+                        width: w,
+                        height: h,
+                        score: 0.0,
+                    },
+                    confidence,
+                    1,
+                ));
+            }
+        }
+    }
+
+    Ok(lines
+        .into_iter()
+        .map(|(mut line, total_confidence, words)| {
+            line.score = total_confidence / words as f32 / 100.0;
+            line
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TSV: &str = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext
+1\t1\t0\t0\t0\t0\t0\t0\t800\t600\t-1\t
+4\t1\t1\t1\t1\t0\t10\t20\t300\t20\t-1\t
+5\t1\t1\t1\t1\t1\t10\t20\t90\t20\t90\tSECRET
+5\t1\t1\t1\t1\t2\t110\t18\t200\t24\t70\ts3cr3t
+5\t1\t1\t1\t2\t1\t10\t50\t50\t20\t-1\t
+5\t1\t1\t1\t2\t2\t10\t50\t50\t20\t95\tnext
+";
+
+    #[test]
+    fn groups_words_into_lines() {
+        let lines = parse_tesseract_tsv(TSV).unwrap();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].text, "SECRET s3cr3t");
+        assert_eq!(
+            (lines[0].x, lines[0].y, lines[0].width, lines[0].height),
+            (10.0, 18.0, 300.0, 24.0)
+        );
+        assert!((lines[0].score - 0.8).abs() < 1e-6);
+        assert_eq!(lines[1].text, "next");
+        assert!((lines[1].score - 0.95).abs() < 1e-6);
+    }
+
+    #[test]
+    fn locates_columns_by_name() {
+        let tsv =
+            "text\tconf\tlevel\tpage_num\tblock_num\tpar_num\tline_num\tleft\ttop\twidth\theight
+hello\t80\t5\t1\t1\t1\t1\t0\t0\t10\t10
+";
+        let lines = parse_tesseract_tsv(tsv).unwrap();
+
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].text, "hello");
+    }
+
+    #[test]
+    fn rejects_missing_columns() {
+        assert!(matches!(
+            parse_tesseract_tsv("level\ttext\n5\thello\n"),
+            Err(CredentialDetectorError::OcrImportError(_))
+        ));
+        assert!(parse_tesseract_tsv("").is_err());
+    }
+}
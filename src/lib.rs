@@ -3,15 +3,60 @@
 //! This library provides functionality to extract text from images using OCR and
 //! detect credentials (API keys, passwords, etc.) within the extracted text.
 
+mod batch;
+mod decoding;
 mod detector;
 mod detectors;
 mod error;
+mod import;
 mod ocr;
+mod raw_image;
+#[cfg(feature = "server")]
+mod server;
+mod sources;
 mod types;
 
 // Public modules and types
+pub use batch::{BatchInput, BatchResult, BatchResults, BatchScanner};
 pub use detector::CredentialDetector;
-pub use detectors::CredentialDetectorLogic;
+pub use detectors::{
+    ApiKeyDetector, ConnectionStringDetector, CredentialDetectorLogic, CreditCardDetector,
+    CryptoPrivateKeyDetector, EmailDetector, IbanDetector, JwtDetector, KeyValueDetector,
+    MyNumberDetector, PassportDetector, PasswordDetector, PhoneNumberDetector, QrPayloadDetector,
+    SeedPhraseDetector, SsnDetector,
+};
 pub use error::CredentialDetectorError;
-pub use ocr::{OcrEngine, TextLine};
-pub use types::{BoundingBox, CredentialDetection};
+/// Re-export of the `image` crate, whose `DynamicImage` is accepted by the detectors
+pub use image;
+pub use import::{parse_alto, parse_hocr, parse_tesseract_tsv};
+#[cfg(feature = "barcode")]
+pub use ocr::BarcodeReader;
+#[cfg(feature = "tesseract")]
+pub use ocr::TesseractEngine;
+pub use ocr::{
+    Inversion, OcrEngine, OrientationConfig, PrecomputedText, PreprocessConfig, TextExtractor,
+    TextLine, TilingConfig,
+};
+pub use raw_image::{PixelFormat, RawImage};
+#[cfg(feature = "server")]
+pub use server::{Server, ServerConfig};
+#[cfg(feature = "archive")]
+pub use sources::ArchiveLimits;
+pub use sources::FrameSampling;
+pub use types::{BoundingBox, CredentialDetection, DetectorCategory, ScanReport, Severity};
+
+// Detectors and their backends are shared between threads; keep them `Send + Sync`
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync + ?Sized>() {}
+
+    assert_send_sync::<CredentialDetector>();
+    assert_send_sync::<BatchScanner>();
+    assert_send_sync::<OcrEngine>();
+    assert_send_sync::<PrecomputedText>();
+    #[cfg(feature = "barcode")]
+    assert_send_sync::<BarcodeReader>();
+    #[cfg(feature = "tesseract")]
+    assert_send_sync::<TesseractEngine>();
+    assert_send_sync::<dyn TextExtractor>();
+    assert_send_sync::<dyn CredentialDetectorLogic>();
+};
@@ -1,3 +1,87 @@
-fn main() {
-    println!("Hello, world!");
+//! Command line interface
+
+use std::env;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: ocr-credential-detector serve [options]
+
+Options:
+    --address <host:port>           Address to listen on (default: 127.0.0.1:8080)
+    --max-body-size <bytes>         Maximum request body size (default: 33554432)
+    --max-concurrent-scans <count>  Maximum scans at once, at least 1 (default: number of CPUs)
+    --pii                           Also detect personally identifiable information";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("serve") => serve(&args[1..]),
+        _ => {
+            eprintln!("{}", USAGE);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Run the HTTP scanning service
+#[cfg(feature = "server")]
+fn serve(args: &[String]) -> ExitCode {
+    use ocr_credential_detector::{CredentialDetector, Server, ServerConfig};
+    use std::sync::Arc;
+
+    let mut config = ServerConfig::default();
+    let mut pii = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let parsed = match arg.as_str() {
+            "--address" => args.next().map(|address| config.address = address.clone()),
+            "--max-body-size" => args
+                .next()
+                .and_then(|size| size.parse().ok())
+                .map(|size| config.max_body_size = size),
+            "--max-concurrent-scans" => args
+                .next()
+                .and_then(|count| count.parse().ok())
+                .filter(|&count| count > 0)
+                .map(|count| config.max_concurrent_scans = count),
+            "--pii" => {
+                pii = true;
+                Some(())
+            }
+            _ => None,
+        };
+        if parsed.is_none() {
+            eprintln!("Invalid argument: {}\n\n{}", arg, USAGE);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let detector = match CredentialDetector::new() {
+        Ok(detector) if pii => detector.with_pii_detectors(),
+        Ok(detector) => detector,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let server = match Server::bind(Arc::new(detector), config) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(address) = server.local_addr() {
+        eprintln!("Listening on http://{}", address);
+    }
+    server.run();
+    ExitCode::SUCCESS
+}
+
+#[cfg(not(feature = "server"))]
+fn serve(_args: &[String]) -> ExitCode {
+    eprintln!("The serve subcommand requires the `server` feature");
+    ExitCode::FAILURE
 }
@@ -1,14 +1,31 @@
 //! Wrapper for ocrs library
 
 use crate::error::CredentialDetectorError;
-use image::{self};
-use ocrs::{ImageSource, OcrEngine as OcrsEngine, OcrEngineParams};
+use crate::ocr::TextExtractor;
+use crate::ocr::orientation::{self, OrientationConfig, Transform};
+use crate::ocr::preprocess::{self, PreprocessConfig};
+use crate::ocr::tiling::{self, TilingConfig};
+use crate::types::BoundingBox;
+use image::{self, DynamicImage, RgbImage, imageops};
+use ocrs::{ImageSource, OcrEngine as OcrsEngine, OcrEngineParams, TextItem};
 use rten::Model;
+use std::borrow::Cow;
 use std::path::Path;
 
 /// Wrapper for OCR engine
+///
+/// The engine is `Send + Sync` and recognition takes `&self`, so one engine with its
+/// loaded models can serve any number of threads; the models are read-only after
+/// loading. Inference runs on the process-wide thread pool of `rten` (sized by the
+/// `RTEN_NUM_THREADS` environment variable), which concurrent calls share.
 pub struct OcrEngine {
     engine: OcrsEngine,
+    /// Tiling applied to large images, if enabled
+    tiling: Option<TilingConfig>,
+    /// Preprocessed variants of the image to run OCR on (the image as is when empty)
+    preprocessing: Vec<PreprocessConfig>,
+    /// Rotation and skew handling
+    orientation: OrientationConfig,
 }
 
 /// Text line information
@@ -28,6 +45,39 @@ pub struct TextLine {
     pub score: f32,
 }
 
+impl TextLine {
+    /// Approximate the bounding box of a byte range of the text
+    ///
+    /// OCR only reports geometry per line, so characters are assumed to be evenly spaced.
+    pub fn substring_box(&self, start: usize, end: usize) -> BoundingBox {
+        let total = self.text.chars().count().max(1) as f32;
+        let before = self.text[..start].chars().count() as f32;
+        let matched = self.text[start..end].chars().count() as f32;
+
+        BoundingBox {
+            x: self.x + self.width * before / total,
+            y: self.y,
+            width: self.width * matched / total,
+            height: self.height,
+        }
+    }
+}
+
+#[cfg(test)]
+impl TextLine {
+    /// Line of text at the top left, 100 pixels wide, for tests
+    pub(crate) fn test_line(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            x: 0.0,
+            y: 0.0,
+            width: 100.0,
+            height: 10.0,
+            score: 1.0,
+        }
+    }
+}
+
 impl OcrEngine {
     /// Create a new OCR engine
     pub fn new() -> Result<Self, CredentialDetectorError> {
@@ -65,12 +115,50 @@ impl OcrEngine {
 
         // Initialize OcrEngine
         match OcrsEngine::new(params) {
-            Ok(engine) => Ok(Self { engine }),
+            Ok(engine) => Ok(Self {
+                engine,
+                tiling: None,
+                preprocessing: Vec::new(),
+                orientation: OrientationConfig::default(),
+            }),
             Err(e) => Err(CredentialDetectorError::OcrEngineInitError(e.to_string())),
         }
     }
 
+    /// Split images larger than the tile size into overlapping tiles before OCR
+    pub fn with_tiling(mut self, config: TilingConfig) -> Self {
+        self.tiling = Some(config);
+        self
+    }
+
+    /// Preprocess images before OCR
+    pub fn with_preprocessing(mut self, config: PreprocessConfig) -> Self {
+        self.preprocessing = vec![config];
+        self
+    }
+
+    /// Run OCR on several preprocessed variants of each image and merge the results
+    ///
+    /// Useful when the same image mixes themes, e.g. a dark terminal next to a light
+    /// browser window.
+    pub fn with_preprocessing_variants(mut self, configs: Vec<PreprocessConfig>) -> Self {
+        self.preprocessing = configs;
+        self
+    }
+
+    /// Set how rotated and skewed images are handled
+    ///
+    /// EXIF orientation is applied by default; rotation detection and deskewing are
+    /// opt-in.
+    pub fn with_orientation(mut self, config: OrientationConfig) -> Self {
+        self.orientation = config;
+        self
+    }
+
     /// Extract text from image
+    ///
+    /// Coordinates are relative to the stored image, before EXIF orientation and any
+    /// rotation applied for OCR.
     pub fn extract_text(
         &self,
         image_data: &[u8],
@@ -81,9 +169,128 @@ impl OcrEngine {
             Err(e) => return Err(CredentialDetectorError::ImageLoadError(e.to_string())),
         };
 
-        // Convert to RGB8
-        let rgb_img = img.to_rgb8();
+        self.extract_text_from_decoded(image_data, &img)
+    }
+
+    /// Extract text from image data that has already been decoded into `img`
+    ///
+    /// `image_data` is only read for its EXIF orientation. Coordinates are relative to
+    /// the stored image, as with [`OcrEngine::extract_text`].
+    pub fn extract_text_from_decoded(
+        &self,
+        image_data: &[u8],
+        img: &DynamicImage,
+    ) -> Result<Vec<TextLine>, CredentialDetectorError> {
+        let mut transform = Transform::default();
+        let img = if self.orientation.exif {
+            orientation::apply_exif(image_data, img, &mut transform)
+        } else {
+            Cow::Borrowed(img)
+        };
+
+        let mut text_lines = self.extract_text_from_image(&img)?;
+        text_lines
+            .iter_mut()
+            .for_each(|line| transform.map_back(line));
+        Ok(text_lines)
+    }
+
+    /// Extract text from a decoded image
+    ///
+    /// Decoded images carry no EXIF metadata and are assumed to be upright as stored.
+    pub fn extract_text_from_image(
+        &self,
+        img: &DynamicImage,
+    ) -> Result<Vec<TextLine>, CredentialDetectorError> {
+        if !self.orientation.auto_rotate {
+            return self.extract_text_turned(img, 0);
+        }
+
+        orientation::most_plausible(orientation::is_vertical(img), |quarter_turns| {
+            self.extract_text_turned(img, quarter_turns)
+        })
+    }
+
+    /// Extract text from an image turned clockwise by `quarter_turns` and deskewed,
+    /// mapping the lines back to the coordinates of the original image
+    fn extract_text_turned(
+        &self,
+        img: &DynamicImage,
+        quarter_turns: u8,
+    ) -> Result<Vec<TextLine>, CredentialDetectorError> {
+        let mut transform = Transform::default();
+        let mut img = Cow::Borrowed(img);
+        if quarter_turns != 0 {
+            img = Cow::Owned(orientation::rotate_quarter_turns(
+                &img,
+                quarter_turns,
+                &mut transform,
+            ));
+        }
+        if self.orientation.deskew {
+            let angle = orientation::estimate_skew(&img, self.orientation.max_skew_degrees);
+            if angle != 0.0 {
+                img = Cow::Owned(orientation::rotate(&img, angle, &mut transform));
+            }
+        }
+
+        let mut text_lines = self.extract_text_upright(&img)?;
+        text_lines
+            .iter_mut()
+            .for_each(|line| transform.map_back(line));
+        Ok(text_lines)
+    }
+
+    /// Extract text from an upright image, applying the preprocessing variants
+    fn extract_text_upright(
+        &self,
+        img: &DynamicImage,
+    ) -> Result<Vec<TextLine>, CredentialDetectorError> {
+        if self.preprocessing.is_empty() {
+            return self.extract_text_from_rgb(&img.to_rgb8());
+        }
+
+        let mut variants = Vec::new();
+        for config in &self.preprocessing {
+            let (processed, scale) = config.apply(img);
+            let mut text_lines = self.extract_text_from_rgb(&processed)?;
+            preprocess::unscale_lines(&mut text_lines, scale);
+            variants.push(text_lines);
+        }
+
+        Ok(preprocess::merge_variant_lines(variants))
+    }
+
+    /// Extract text from an RGB image, tiling it if it is large
+    fn extract_text_from_rgb(
+        &self,
+        rgb_img: &RgbImage,
+    ) -> Result<Vec<TextLine>, CredentialDetectorError> {
+        match &self.tiling {
+            Some(config) if config.applies_to(rgb_img.width(), rgb_img.height()) => {
+                let mut text_lines = Vec::new();
+
+                for tile in config.tiles(rgb_img.width(), rgb_img.height()) {
+                    let tile_img =
+                        imageops::crop_imm(rgb_img, tile.x, tile.y, tile.width, tile.height)
+                            .to_image();
 
+                    // Move the lines back to full-image coordinates
+                    for mut line in self.recognize(&tile_img)? {
+                        line.x += tile.x as f32;
+                        line.y += tile.y as f32;
+                        text_lines.push(line);
+                    }
+                }
+
+                Ok(tiling::merge_tile_lines(text_lines))
+            }
+            _ => self.recognize(rgb_img),
+        }
+    }
+
+    /// Run text detection and recognition on an RGB image
+    fn recognize(&self, rgb_img: &RgbImage) -> Result<Vec<TextLine>, CredentialDetectorError> {
         // Create ImageSource
         let img_source = match ImageSource::from_bytes(rgb_img.as_raw(), rgb_img.dimensions()) {
             Ok(source) => source,
@@ -119,20 +326,39 @@ impl OcrEngine {
                 continue; // Skip lines with 1 or fewer characters (noise removal)
             }
 
-            // Adjust according to the actual API of ocrs::TextLine
-            // In the example, line.to_string() was used, but
-            // the use of bbox() and score() methods could not be confirmed.
-            // Here, we attempt to access the fields directly.
+            // ocrs does not report a confidence for recognized lines
+            let rect = line.bounding_rect();
             text_lines.push(TextLine {
                 text: line.to_string(),
-                x: 0.0,      // Adjust according to the actual API
-                y: 0.0,      // Adjust according to the actual API
-                width: 0.0,  // Adjust according to the actual API
-                height: 0.0, // Adjust according to the actual API
-                score: 1.0,  // Adjust according to the actual API
+                x: rect.left() as f32,
+                y: rect.top() as f32,
+                width: rect.width() as f32,
+                height: rect.height() as f32,
+                score: 1.0,
             });
         }
 
         Ok(text_lines)
     }
 }
+
+impl TextExtractor for OcrEngine {
+    fn extract_text(&self, image_data: &[u8]) -> Result<Vec<TextLine>, CredentialDetectorError> {
+        OcrEngine::extract_text(self, image_data)
+    }
+
+    fn extract_text_from_decoded(
+        &self,
+        image_data: &[u8],
+        image: &DynamicImage,
+    ) -> Result<Vec<TextLine>, CredentialDetectorError> {
+        OcrEngine::extract_text_from_decoded(self, image_data, image)
+    }
+
+    fn extract_text_from_image(
+        &self,
+        image: &DynamicImage,
+    ) -> Result<Vec<TextLine>, CredentialDetectorError> {
+        OcrEngine::extract_text_from_image(self, image)
+    }
+}
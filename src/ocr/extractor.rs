@@ -0,0 +1,164 @@
+//! Text extraction backends
+
+use crate::error::CredentialDetectorError;
+use crate::ocr::TextLine;
+use image::{DynamicImage, ImageOutputFormat};
+use std::io::Cursor;
+use std::sync::Arc;
+
+/// Text extraction interface
+///
+/// Implemented by [`crate::OcrEngine`] (ocrs), [`PrecomputedText`] and, with the
+/// `tesseract` feature, `TesseractEngine`. Closures taking the image data can be used
+/// as mocks.
+///
+/// Extractors are shared between the worker threads of a [`crate::BatchScanner`] or a
+/// server, so they must be `Send + Sync`. Wrap an extractor in an [`Arc`] to use one set
+/// of loaded models from several credential detectors (e.g. with different detectors
+/// enabled).
+pub trait TextExtractor: Send + Sync {
+    /// Extracts text lines from encoded image data
+    fn extract_text(&self, image_data: &[u8]) -> Result<Vec<TextLine>, CredentialDetectorError>;
+
+    /// Extracts text lines from a decoded image
+    ///
+    /// Backends that work on pixels should override this; by default the image is
+    /// encoded as PNG and passed to [`TextExtractor::extract_text`].
+    fn extract_text_from_image(
+        &self,
+        image: &DynamicImage,
+    ) -> Result<Vec<TextLine>, CredentialDetectorError> {
+        let mut image_data = Vec::new();
+        if let Err(e) = image.write_to(&mut Cursor::new(&mut image_data), ImageOutputFormat::Png) {
+            return Err(CredentialDetectorError::ImageLoadError(e.to_string()));
+        }
+        self.extract_text(&image_data)
+    }
+
+    /// Extracts text lines from encoded image data that the caller has already decoded
+    ///
+    /// Lets OCR and the barcode reader share one decoded image. Backends that decode
+    /// the data themselves should override this to use the decoded image; by default the
+    /// encoded data is passed to [`TextExtractor::extract_text`].
+    fn extract_text_from_decoded(
+        &self,
+        image_data: &[u8],
+        _image: &DynamicImage,
+    ) -> Result<Vec<TextLine>, CredentialDetectorError> {
+        self.extract_text(image_data)
+    }
+}
+
+impl<F> TextExtractor for F
+where
+    F: Fn(&[u8]) -> Result<Vec<TextLine>, CredentialDetectorError> + Send + Sync,
+{
+    fn extract_text(&self, image_data: &[u8]) -> Result<Vec<TextLine>, CredentialDetectorError> {
+        self(image_data)
+    }
+}
+
+impl<T: TextExtractor + ?Sized> TextExtractor for Arc<T> {
+    fn extract_text(&self, image_data: &[u8]) -> Result<Vec<TextLine>, CredentialDetectorError> {
+        (**self).extract_text(image_data)
+    }
+
+    fn extract_text_from_image(
+        &self,
+        image: &DynamicImage,
+    ) -> Result<Vec<TextLine>, CredentialDetectorError> {
+        (**self).extract_text_from_image(image)
+    }
+
+    fn extract_text_from_decoded(
+        &self,
+        image_data: &[u8],
+        image: &DynamicImage,
+    ) -> Result<Vec<TextLine>, CredentialDetectorError> {
+        (**self).extract_text_from_decoded(image_data, image)
+    }
+}
+
+/// Text extractor returning text lines recognized beforehand
+///
+/// Useful when images are OCRed upstream; the image data is ignored.
+#[derive(Debug, Clone, Default)]
+pub struct PrecomputedText {
+    text_lines: Vec<TextLine>,
+}
+
+impl PrecomputedText {
+    /// Create a new extractor returning the given text lines
+    pub fn new(text_lines: Vec<TextLine>) -> Self {
+        Self { text_lines }
+    }
+}
+
+impl TextExtractor for PrecomputedText {
+    fn extract_text(&self, _image_data: &[u8]) -> Result<Vec<TextLine>, CredentialDetectorError> {
+        Ok(self.text_lines.clone())
+    }
+
+    fn extract_text_from_image(
+        &self,
+        _image: &DynamicImage,
+    ) -> Result<Vec<TextLine>, CredentialDetectorError> {
+        Ok(self.text_lines.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::CredentialDetector;
+    use image::{ImageFormat, RgbImage};
+
+    fn png() -> Vec<u8> {
+        let mut data = Vec::new();
+        RgbImage::new(8, 8)
+            .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+            .unwrap();
+        data
+    }
+
+    /// Number of detections from encoded and from decoded images
+    fn detections(detector: &CredentialDetector) -> (usize, usize) {
+        let image = DynamicImage::ImageRgb8(RgbImage::new(8, 8));
+        (
+            detector.detect_from_image(&png()).unwrap().len(),
+            detector.detect_from_decoded_image(&image).unwrap().len(),
+        )
+    }
+
+    #[test]
+    fn drives_the_detector() {
+        let lines = vec![TextLine::test_line("password: hunter2")];
+
+        let precomputed = PrecomputedText::new(lines.clone());
+        assert_eq!(
+            detections(&CredentialDetector::from_text_extractor(precomputed)),
+            (1, 1)
+        );
+
+        let closure = move |_: &[u8]| Ok(lines.clone());
+        assert_eq!(
+            detections(&CredentialDetector::from_text_extractor(closure)),
+            (1, 1)
+        );
+    }
+
+    #[test]
+    fn shares_one_extractor_through_arc() {
+        let lines = vec![TextLine::test_line("password: hunter2")];
+        let extractor: Arc<dyn TextExtractor> = Arc::new(PrecomputedText::new(lines));
+
+        let detector = CredentialDetector::from_text_extractor(Arc::clone(&extractor));
+        let without_passwords = CredentialDetector::from_text_extractor(Arc::clone(&extractor))
+            .without_detector("password")
+            .without_detector("key-value-secret");
+
+        assert_eq!(detections(&detector), (1, 1));
+        assert_eq!(detections(&without_passwords), (0, 0));
+        assert_eq!(Arc::strong_count(&extractor), 3);
+    }
+}
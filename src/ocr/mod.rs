@@ -1,6 +1,22 @@
 //! OCR related implementations
 
+#[cfg(feature = "barcode")]
+mod barcode;
 mod engine;
+mod extractor;
+mod orientation;
+mod preprocess;
+#[cfg(feature = "tesseract")]
+mod tesseract;
+mod tiling;
 
+#[cfg(feature = "barcode")]
+pub use barcode::BarcodeReader;
 pub use engine::OcrEngine;
 pub use engine::TextLine;
+pub use extractor::{PrecomputedText, TextExtractor};
+pub use orientation::OrientationConfig;
+pub use preprocess::{Inversion, PreprocessConfig};
+#[cfg(feature = "tesseract")]
+pub use tesseract::TesseractEngine;
+pub use tiling::TilingConfig;
@@ -0,0 +1,551 @@
+//! Rotation and skew handling
+//!
+//! Phone photos of monitors and sticky notes are rotated or slightly skewed, while the
+//! OCR models expect upright, horizontal text. Images are turned upright (EXIF
+//! orientation, quarter turns) and deskewed before OCR, and the recognized lines are
+//! mapped back to the coordinates of the stored image.
+
+use crate::ocr::TextLine;
+use crate::ocr::preprocess;
+use image::imageops::FilterType;
+use image::{DynamicImage, GrayImage, Rgb, RgbImage};
+use std::borrow::Cow;
+use std::io::Cursor;
+
+/// Longest side of the thumbnails used to analyse the layout of an image
+const ANALYSIS_SIZE: u32 = 1000;
+
+/// Step of the skew angle search in degrees
+const SKEW_STEP_DEGREES: f32 = 0.25;
+
+/// Orientation handling configuration
+#[derive(Debug, Clone)]
+pub struct OrientationConfig {
+    /// Apply the EXIF orientation of photos
+    pub exif: bool,
+    /// Detect text turned by 90, 180 or 270 degrees
+    ///
+    /// Recognition decides between the orientations, so the image is recognized twice,
+    /// and three times when its layout suggests vertical text.
+    pub auto_rotate: bool,
+    /// Straighten text lines that are slightly tilted
+    pub deskew: bool,
+    /// Largest tilt corrected by deskewing, in degrees
+    pub max_skew_degrees: f32,
+}
+
+impl Default for OrientationConfig {
+    fn default() -> Self {
+        Self {
+            exif: true,
+            auto_rotate: false,
+            deskew: false,
+            max_skew_degrees: 10.0,
+        }
+    }
+}
+
+impl OrientationConfig {
+    /// Configuration enabling every correction
+    pub fn all() -> Self {
+        Self {
+            auto_rotate: true,
+            deskew: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// Geometric operation applied to an image before OCR
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    /// Quarter turns clockwise of an image of the given size
+    QuarterTurns { turns: u8, width: f32, height: f32 },
+    /// Mirror along the vertical axis of an image of the given width
+    FlipHorizontal { width: f32 },
+    /// Mirror along the horizontal axis of an image of the given height
+    FlipVertical { height: f32 },
+    /// Rotation by `-angle` radians around the centres of the source and rotated images
+    Rotate {
+        angle: f32,
+        source_center: (f32, f32),
+        rotated_center: (f32, f32),
+    },
+}
+
+impl Step {
+    /// Map a point of the transformed image back to the image before the step
+    fn map_back(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        match *self {
+            Step::QuarterTurns {
+                turns,
+                width,
+                height,
+            } => match turns % 4 {
+                1 => (y, height - x),
+                2 => (width - x, height - y),
+                3 => (width - y, x),
+                _ => (x, y),
+            },
+            Step::FlipHorizontal { width } => (width - x, y),
+            Step::FlipVertical { height } => (x, height - y),
+            Step::Rotate {
+                angle,
+                source_center,
+                rotated_center,
+            } => {
+                let (dx, dy) = (x - rotated_center.0, y - rotated_center.1);
+                let (sin, cos) = angle.sin_cos();
+                (
+                    dx * cos - dy * sin + source_center.0,
+                    dx * sin + dy * cos + source_center.1,
+                )
+            }
+        }
+    }
+}
+
+/// Geometric operations applied to an image, to map results back to the original
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Transform {
+    steps: Vec<Step>,
+}
+
+impl Transform {
+    /// Map a text line found in the transformed image back to original image coordinates
+    ///
+    /// Rotated boxes are replaced by the axis-aligned box enclosing them.
+    pub fn map_back(&self, line: &mut TextLine) {
+        if self.steps.is_empty() {
+            return;
+        }
+
+        let corners = [
+            (line.x, line.y),
+            (line.x + line.width, line.y),
+            (line.x, line.y + line.height),
+            (line.x + line.width, line.y + line.height),
+        ]
+        .map(|corner| {
+            self.steps
+                .iter()
+                .rev()
+                .fold(corner, |p, step| step.map_back(p))
+        });
+
+        let min_x = corners.iter().map(|p| p.0).fold(f32::INFINITY, f32::min);
+        let min_y = corners.iter().map(|p| p.1).fold(f32::INFINITY, f32::min);
+        let max_x = corners
+            .iter()
+            .map(|p| p.0)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let max_y = corners
+            .iter()
+            .map(|p| p.1)
+            .fold(f32::NEG_INFINITY, f32::max);
+
+        line.x = min_x;
+        line.y = min_y;
+        line.width = max_x - min_x;
+        line.height = max_y - min_y;
+    }
+}
+
+/// Read the EXIF orientation (1-8) of encoded image data
+fn exif_orientation(image_data: &[u8]) -> Option<u32> {
+    let exif = exif::Reader::new()
+        .read_from_container(&mut Cursor::new(image_data))
+        .ok()?;
+    exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)?
+        .value
+        .get_uint(0)
+}
+
+/// Turn an image by quarter turns clockwise
+pub(crate) fn rotate_quarter_turns(
+    image: &DynamicImage,
+    turns: u8,
+    transform: &mut Transform,
+) -> DynamicImage {
+    let (width, height) = (image.width() as f32, image.height() as f32);
+    let rotated = match turns % 4 {
+        1 => image.rotate90(),
+        2 => image.rotate180(),
+        3 => image.rotate270(),
+        _ => return image.clone(),
+    };
+
+    transform.steps.push(Step::QuarterTurns {
+        turns,
+        width,
+        height,
+    });
+    rotated
+}
+
+/// Apply the EXIF orientation of the image, as image viewers do
+pub(crate) fn apply_exif<'a>(
+    image_data: &[u8],
+    image: &'a DynamicImage,
+    transform: &mut Transform,
+) -> Cow<'a, DynamicImage> {
+    let flip_horizontal = |image: &DynamicImage, transform: &mut Transform| {
+        transform.steps.push(Step::FlipHorizontal {
+            width: image.width() as f32,
+        });
+        image.fliph()
+    };
+
+    Cow::Owned(match exif_orientation(image_data) {
+        Some(2) => flip_horizontal(image, transform),
+        Some(3) => rotate_quarter_turns(image, 2, transform),
+        Some(4) => {
+            transform.steps.push(Step::FlipVertical {
+                height: image.height() as f32,
+            });
+            image.flipv()
+        }
+        Some(5) => {
+            let rotated = rotate_quarter_turns(image, 1, transform);
+            flip_horizontal(&rotated, transform)
+        }
+        Some(6) => rotate_quarter_turns(image, 1, transform),
+        Some(7) => {
+            let rotated = rotate_quarter_turns(image, 3, transform);
+            flip_horizontal(&rotated, transform)
+        }
+        Some(8) => rotate_quarter_turns(image, 3, transform),
+        _ => return Cow::Borrowed(image),
+    })
+}
+
+/// Text pixels of a binarised thumbnail of an image
+struct Foreground {
+    width: u32,
+    height: u32,
+    /// Coordinates of the text pixels
+    points: Vec<(f32, f32)>,
+}
+
+impl Foreground {
+    fn new(image: &DynamicImage) -> Self {
+        let thumbnail = image.resize(ANALYSIS_SIZE, ANALYSIS_SIZE, FilterType::Triangle);
+        let gray: GrayImage = thumbnail.to_luma8();
+        let threshold = preprocess::otsu_threshold(&gray);
+
+        // Text is the minority class, whether dark on light or light on dark
+        let bright = gray.pixels().filter(|p| p.0[0] > threshold).count();
+        let text_is_bright = bright * 2 < gray.pixels().len();
+
+        let points = gray
+            .enumerate_pixels()
+            .filter(|(_, _, p)| (p.0[0] > threshold) == text_is_bright)
+            .map(|(x, y, _)| (x as f32, y as f32))
+            .collect();
+
+        Self {
+            width: gray.width(),
+            height: gray.height(),
+            points,
+        }
+    }
+
+    /// Squared coefficient of variation of a projection profile
+    fn profile_variation(profile: &[u64]) -> f64 {
+        let n = profile.len().max(1) as f64;
+        let mean = profile.iter().sum::<u64>() as f64 / n;
+        if mean == 0.0 {
+            return 0.0;
+        }
+        let variance = profile
+            .iter()
+            .map(|&count| (count as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        variance / (mean * mean)
+    }
+
+    /// Determine if text runs vertically (the image is turned by 90 or 270 degrees)
+    ///
+    /// Lines of text separated by blank gaps make the row profile of horizontal text
+    /// much more uneven than its column profile.
+    fn is_vertical(&self) -> bool {
+        let mut rows = vec![0u64; self.height as usize];
+        let mut columns = vec![0u64; self.width as usize];
+        for &(x, y) in &self.points {
+            rows[y as usize] += 1;
+            columns[x as usize] += 1;
+        }
+
+        Self::profile_variation(&columns) > Self::profile_variation(&rows)
+    }
+
+    /// Sharpness of the row profile after rotating the text pixels by `-angle` radians
+    fn alignment(&self, angle: f32) -> u64 {
+        let (sin, cos) = angle.sin_cos();
+        let diagonal = (self.width + self.height) as f32;
+        let mut rows = vec![0u64; 2 * diagonal as usize + 1];
+
+        for &(x, y) in &self.points {
+            let row = -x * sin + y * cos + diagonal;
+            rows[row as usize] += 1;
+        }
+
+        // Rows line up with text lines when the sum of squares peaks
+        rows.iter().map(|&count| count * count).sum()
+    }
+}
+
+/// Determine if the text in an image runs vertically
+pub(crate) fn is_vertical(image: &DynamicImage) -> bool {
+    Foreground::new(image).is_vertical()
+}
+
+/// Estimate the tilt of the text lines in radians, positive when lines go down to the right
+pub(crate) fn estimate_skew(image: &DynamicImage, max_skew_degrees: f32) -> f32 {
+    let foreground = Foreground::new(image);
+    if foreground.points.is_empty() {
+        return 0.0;
+    }
+
+    let steps = (max_skew_degrees / SKEW_STEP_DEGREES).round() as i32;
+    (-steps..=steps)
+        .map(|step| (step as f32 * SKEW_STEP_DEGREES).to_radians())
+        .max_by_key(|&angle| foreground.alignment(angle))
+        .unwrap_or(0.0)
+}
+
+/// Sample an image at a fractional position with bilinear interpolation
+fn sample(image: &RgbImage, x: f32, y: f32) -> Option<Rgb<u8>> {
+    let (width, height) = image.dimensions();
+    if x < 0.0 || y < 0.0 || x > (width - 1) as f32 || y > (height - 1) as f32 {
+        return None;
+    }
+
+    let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+    let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+    let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+
+    let pixel = |px: u32, py: u32| image.get_pixel(px, py).0.map(|c| c as f32);
+    let (p00, p10, p01, p11) = (pixel(x0, y0), pixel(x1, y0), pixel(x0, y1), pixel(x1, y1));
+
+    let mut value = [0u8; 3];
+    for (c, v) in value.iter_mut().enumerate() {
+        let top = p00[c] * (1.0 - fx) + p10[c] * fx;
+        let bottom = p01[c] * (1.0 - fx) + p11[c] * fx;
+        *v = (top * (1.0 - fy) + bottom * fy).round() as u8;
+    }
+    Some(Rgb(value))
+}
+
+/// Rotate an image by `-angle` radians so that text tilted by `angle` becomes horizontal
+///
+/// The canvas grows to fit the rotated image; uncovered areas take the colour of the
+/// image corners so that no artificial edges are introduced.
+pub(crate) fn rotate(image: &DynamicImage, angle: f32, transform: &mut Transform) -> DynamicImage {
+    let source = image.to_rgb8();
+    let (width, height) = (source.width() as f32, source.height() as f32);
+    let (sin, cos) = angle.sin_cos();
+
+    let rotated_width = (width * cos.abs() + height * sin.abs()).ceil() as u32;
+    let rotated_height = (width * sin.abs() + height * cos.abs()).ceil() as u32;
+
+    let corners = [
+        source.get_pixel(0, 0),
+        source.get_pixel(source.width() - 1, 0),
+        source.get_pixel(0, source.height() - 1),
+        source.get_pixel(source.width() - 1, source.height() - 1),
+    ];
+    let mut background = [0u8; 3];
+    for (c, v) in background.iter_mut().enumerate() {
+        *v = (corners.iter().map(|p| p.0[c] as u32).sum::<u32>() / 4) as u8;
+    }
+
+    let step = Step::Rotate {
+        angle,
+        source_center: (width / 2.0, height / 2.0),
+        rotated_center: (rotated_width as f32 / 2.0, rotated_height as f32 / 2.0),
+    };
+
+    let rotated = RgbImage::from_fn(rotated_width, rotated_height, |x, y| {
+        let (sx, sy) = step.map_back((x as f32, y as f32));
+        sample(&source, sx, sy).unwrap_or(Rgb(background))
+    });
+
+    transform.steps.push(step);
+    DynamicImage::ImageRgb8(rotated)
+}
+
+/// Estimate how much recognized text looks like real text rather than misreads
+///
+/// Upside-down text is recognized as short fragments and punctuation, so the
+/// characters of alphanumeric tokens are counted.
+pub(crate) fn plausibility(text_lines: &[TextLine]) -> usize {
+    text_lines
+        .iter()
+        .flat_map(|line| line.text.split_whitespace())
+        .filter(|token| token.chars().filter(|c| c.is_alphanumeric()).count() >= 3)
+        .map(|token| token.chars().filter(|c| c.is_alphanumeric()).count())
+        .sum()
+}
+
+/// Recognize an image in the orientations its layout allows and keep the most plausible
+///
+/// `recognize` reads the image turned clockwise by the given quarter turns. Layout
+/// analysis cannot tell which way text runs, so recognition decides: `vertical` images
+/// are read as is and turned either way, other images as is and upside down. The image
+/// is only turned when that reads better.
+pub(crate) fn most_plausible<E>(
+    vertical: bool,
+    mut recognize: impl FnMut(u8) -> Result<Vec<TextLine>, E>,
+) -> Result<Vec<TextLine>, E> {
+    let turns: &[u8] = if vertical { &[1, 3] } else { &[2] };
+
+    let mut text_lines = recognize(0)?;
+    for &turns in turns {
+        let turned_lines = recognize(turns)?;
+        if plausibility(&turned_lines) > plausibility(&text_lines) {
+            text_lines = turned_lines;
+        }
+    }
+    Ok(text_lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Black image with a single white pixel
+    fn marked_image(width: u32, height: u32, x: u32, y: u32) -> DynamicImage {
+        let mut image = RgbImage::new(width, height);
+        image.put_pixel(x, y, Rgb([255, 255, 255]));
+        DynamicImage::ImageRgb8(image)
+    }
+
+    /// Position of the brightest pixel
+    fn find_mark(image: &DynamicImage) -> (u32, u32) {
+        let image = image.to_luma8();
+        let (x, y, _) = image
+            .enumerate_pixels()
+            .max_by_key(|(_, _, p)| p.0[0])
+            .unwrap();
+        (x, y)
+    }
+
+    fn pixel_line(x: u32, y: u32) -> TextLine {
+        TextLine {
+            text: String::new(),
+            x: x as f32,
+            y: y as f32,
+            width: 1.0,
+            height: 1.0,
+            score: 1.0,
+        }
+    }
+
+    #[test]
+    fn empty_transform_keeps_lines() {
+        let mut line = pixel_line(3, 4);
+        Transform::default().map_back(&mut line);
+        assert_eq!(
+            (line.x, line.y, line.width, line.height),
+            (3.0, 4.0, 1.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn maps_quarter_turns_back() {
+        let image = marked_image(40, 30, 7, 3);
+
+        for turns in 1..=3 {
+            let mut transform = Transform::default();
+            let rotated = rotate_quarter_turns(&image, turns, &mut transform);
+            let (x, y) = find_mark(&rotated);
+
+            let mut line = pixel_line(x, y);
+            transform.map_back(&mut line);
+            assert_eq!(
+                (line.x, line.y, line.width, line.height),
+                (7.0, 3.0, 1.0, 1.0),
+                "{turns} turns"
+            );
+        }
+    }
+
+    #[test]
+    fn maps_combined_steps_back() {
+        // EXIF orientation 5: a quarter turn followed by a horizontal flip
+        let image = marked_image(40, 30, 7, 3);
+        let mut transform = Transform::default();
+        let rotated = rotate_quarter_turns(&image, 1, &mut transform);
+        transform.steps.push(Step::FlipHorizontal {
+            width: rotated.width() as f32,
+        });
+        let flipped = rotated.fliph();
+        let (x, y) = find_mark(&flipped);
+
+        let mut line = pixel_line(x, y);
+        transform.map_back(&mut line);
+        assert_eq!((line.x, line.y), (7.0, 3.0));
+    }
+
+    #[test]
+    fn maps_deskew_rotation_back() {
+        let mut image = RgbImage::new(200, 100);
+        for (x, y) in [(150, 20), (151, 20), (150, 21), (151, 21)] {
+            image.put_pixel(x, y, Rgb([255, 255, 255]));
+        }
+        let image = DynamicImage::ImageRgb8(image);
+
+        let mut transform = Transform::default();
+        let rotated = rotate(&image, 5f32.to_radians(), &mut transform);
+        assert!(rotated.width() > 200 && rotated.height() > 100);
+        let (x, y) = find_mark(&rotated);
+
+        let mut line = pixel_line(x, y);
+        transform.map_back(&mut line);
+        let center = (line.x + line.width / 2.0, line.y + line.height / 2.0);
+        assert!((center.0 - 151.0).abs() < 1.5, "{center:?}");
+        assert!((center.1 - 21.0).abs() < 1.5, "{center:?}");
+        // The box of a rotated pixel encloses the rotated square
+        assert!(line.width > 1.0 && line.height > 1.0);
+    }
+
+    #[test]
+    fn keeps_the_orientation_that_reads_best() {
+        let read = |texts: [&'static str; 4]| {
+            move |turns: u8| -> Result<Vec<TextLine>, ()> {
+                Ok(vec![TextLine::test_line(texts[turns as usize])])
+            }
+        };
+        let text = |lines: Vec<TextLine>| lines[0].text.clone();
+
+        // A narrow, left-aligned screenshot looks vertical but reads best as is
+        let upright = read(["password hunter2", "1d 'l", "Zr?", ". ,"]);
+        assert_eq!(
+            text(most_plausible(true, upright).unwrap()),
+            "password hunter2"
+        );
+
+        // Turned text reads best turned either way
+        let turned = read(["1d 'l", "password hunter2", "Zr?", ". ,"]);
+        assert_eq!(
+            text(most_plausible(true, turned).unwrap()),
+            "password hunter2"
+        );
+        let turned_back = read(["1d 'l", "Zr?", ". ,", "password hunter2"]);
+        assert_eq!(
+            text(most_plausible(true, turned_back).unwrap()),
+            "password hunter2"
+        );
+
+        // Horizontal layouts are never turned by a quarter
+        let mut tried = Vec::new();
+        most_plausible(false, |turns| {
+            tried.push(turns);
+            Ok::<_, ()>(Vec::new())
+        })
+        .unwrap();
+        assert_eq!(tried, [0, 2]);
+    }
+}
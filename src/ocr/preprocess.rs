@@ -0,0 +1,355 @@
+//! Image preprocessing before OCR
+//!
+//! Dark-theme terminals, low-contrast themes and tiny fonts are hard to recognize as
+//! is. Images are upscaled, normalised and optionally binarised before OCR, and the
+//! coordinates of the recognized lines are mapped back to the original image.
+
+use crate::ocr::TextLine;
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, GrayImage, Luma, RgbImage};
+
+/// Inversion of light-on-dark images
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inversion {
+    /// Never invert
+    Never,
+    /// Always invert
+    Always,
+    /// Invert images that are mostly dark (dark-mode terminals and editors)
+    Auto,
+}
+
+/// Preprocessing applied to an image before OCR
+#[derive(Debug, Clone)]
+pub struct PreprocessConfig {
+    /// Scale factor applied to the image (1.0 keeps the original size)
+    pub upscale: f32,
+    /// Convert the image to grayscale
+    pub grayscale: bool,
+    /// Stretch the intensity histogram to the full range
+    pub normalize_contrast: bool,
+    /// Inversion of light-on-dark images
+    pub invert: Inversion,
+    /// Apply an unsharp mask
+    pub sharpen: bool,
+    /// Binarise the image with Otsu's threshold
+    pub binarize: bool,
+}
+
+impl Default for PreprocessConfig {
+    fn default() -> Self {
+        Self {
+            upscale: 1.0,
+            grayscale: true,
+            normalize_contrast: true,
+            invert: Inversion::Auto,
+            sharpen: false,
+            binarize: false,
+        }
+    }
+}
+
+impl PreprocessConfig {
+    /// Configuration leaving the image untouched
+    pub fn none() -> Self {
+        Self {
+            upscale: 1.0,
+            grayscale: false,
+            normalize_contrast: false,
+            invert: Inversion::Never,
+            sharpen: false,
+            binarize: false,
+        }
+    }
+
+    /// Configuration for small text: upscaled 2x, sharpened and binarised
+    pub fn small_text() -> Self {
+        Self {
+            upscale: 2.0,
+            sharpen: true,
+            binarize: true,
+            ..Self::default()
+        }
+    }
+
+    /// Apply the preprocessing, returning the processed image and its scale factor
+    pub(crate) fn apply(&self, image: &DynamicImage) -> (RgbImage, f32) {
+        let mut image = image.clone();
+
+        let scale = if self.upscale > 0.0 {
+            self.upscale
+        } else {
+            1.0
+        };
+        if scale != 1.0 {
+            let width = ((image.width() as f32 * scale).round() as u32).max(1);
+            let height = ((image.height() as f32 * scale).round() as u32).max(1);
+            image = image.resize_exact(width, height, FilterType::CatmullRom);
+        }
+
+        let needs_gray = self.grayscale || self.normalize_contrast || self.binarize;
+        if needs_gray {
+            let mut gray = image.to_luma8();
+
+            if self.should_invert(&gray) {
+                imageops::invert(&mut gray);
+            }
+            if self.normalize_contrast {
+                normalize_contrast(&mut gray);
+            }
+            if self.sharpen {
+                gray = imageops::unsharpen(&gray, 1.0, 4);
+            }
+            if self.binarize {
+                binarize(&mut gray);
+            }
+
+            image = DynamicImage::ImageLuma8(gray);
+        } else {
+            if self.should_invert(&image.to_luma8()) {
+                image.invert();
+            }
+            if self.sharpen {
+                image = image.unsharpen(1.0, 4);
+            }
+        }
+
+        (image.to_rgb8(), scale)
+    }
+
+    /// Determine if the image should be inverted
+    fn should_invert(&self, gray: &GrayImage) -> bool {
+        match self.invert {
+            Inversion::Never => false,
+            Inversion::Always => true,
+            Inversion::Auto => is_dark(gray),
+        }
+    }
+}
+
+/// Determine if an image is mostly dark, i.e. light text on a dark background
+fn is_dark(gray: &GrayImage) -> bool {
+    let pixels = gray.as_raw();
+    if pixels.is_empty() {
+        return false;
+    }
+
+    let total: u64 = pixels.iter().map(|&p| p as u64).sum();
+    total / (pixels.len() as u64) < 128
+}
+
+/// Histogram of 8-bit intensities
+fn histogram(gray: &GrayImage) -> [u64; 256] {
+    let mut histogram = [0u64; 256];
+    for &p in gray.as_raw() {
+        histogram[p as usize] += 1;
+    }
+    histogram
+}
+
+/// First intensity, in the given order, at which the cumulative count exceeds `cutoff`
+fn first_above(histogram: &[u64; 256], order: impl Iterator<Item = usize>, cutoff: u64) -> usize {
+    let mut count = 0;
+    for i in order {
+        count += histogram[i];
+        if count > cutoff {
+            return i;
+        }
+    }
+    0
+}
+
+/// Stretch intensities between the 1st and 99th percentiles to the full range
+fn normalize_contrast(gray: &mut GrayImage) {
+    let histogram = histogram(gray);
+    let total: u64 = histogram.iter().sum();
+    let cutoff = total / 100;
+
+    let low = first_above(&histogram, 0..256, cutoff) as f32;
+    let high = first_above(&histogram, (0..256).rev(), cutoff) as f32;
+    if high <= low {
+        return;
+    }
+
+    for Luma([p]) in gray.pixels_mut() {
+        *p = ((*p as f32 - low) * 255.0 / (high - low)).clamp(0.0, 255.0) as u8;
+    }
+}
+
+/// Otsu's threshold of an image, separating dark and bright pixels
+pub(crate) fn otsu_threshold(gray: &GrayImage) -> u8 {
+    let histogram = histogram(gray);
+    let total: u64 = histogram.iter().sum();
+    let sum_all: u64 = histogram
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| i as u64 * count)
+        .sum();
+
+    // Threshold maximising the between-class variance
+    let mut best = (0.0, 0u8);
+    let mut weight_background = 0u64;
+    let mut sum_background = 0u64;
+    for (i, &count) in histogram.iter().enumerate() {
+        weight_background += count;
+        sum_background += i as u64 * count;
+        let weight_foreground = total - weight_background;
+        if weight_background == 0 || weight_foreground == 0 {
+            continue;
+        }
+
+        let mean_background = sum_background as f64 / weight_background as f64;
+        let mean_foreground = (sum_all - sum_background) as f64 / weight_foreground as f64;
+        let variance = weight_background as f64
+            * weight_foreground as f64
+            * (mean_background - mean_foreground).powi(2);
+        if variance > best.0 {
+            best = (variance, i as u8);
+        }
+    }
+
+    best.1
+}
+
+/// Binarise an image with Otsu's threshold
+fn binarize(gray: &mut GrayImage) {
+    let threshold = otsu_threshold(gray);
+    for Luma([p]) in gray.pixels_mut() {
+        *p = if *p > threshold { 255 } else { 0 };
+    }
+}
+
+/// Map the lines recognized in a scaled image back to original image coordinates
+pub(crate) fn unscale_lines(lines: &mut [TextLine], scale: f32) {
+    for line in lines {
+        line.x /= scale;
+        line.y /= scale;
+        line.width /= scale;
+        line.height /= scale;
+    }
+}
+
+/// Merge the lines recognized in several variants of an image
+///
+/// Identical reads of the same line are kept once; different reads are all kept so
+/// that detectors get every chance to match.
+pub(crate) fn merge_variant_lines(variants: Vec<Vec<TextLine>>) -> Vec<TextLine> {
+    let mut merged: Vec<TextLine> = Vec::new();
+
+    for line in variants.into_iter().flatten() {
+        let duplicate = merged.iter().any(|kept| {
+            kept.text == line.text
+                && (kept.x - line.x).abs() < kept.height.max(line.height)
+                && (kept.y - line.y).abs() < kept.height.max(line.height)
+        });
+        if !duplicate {
+            merged.push(line);
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Gray image with `background` everywhere but the pixel at (1, 1)
+    fn gray(background: u8, pixel: u8) -> DynamicImage {
+        let mut image = GrayImage::from_pixel(8, 8, Luma([background]));
+        image.put_pixel(1, 1, Luma([pixel]));
+        DynamicImage::ImageLuma8(image)
+    }
+
+    fn luma(image: &RgbImage, x: u32, y: u32) -> u8 {
+        image.get_pixel(x, y).0[0]
+    }
+
+    #[test]
+    fn upscales_and_reports_the_scale() {
+        let config = PreprocessConfig {
+            upscale: 2.0,
+            ..PreprocessConfig::none()
+        };
+        let (image, scale) = config.apply(&gray(255, 0));
+        assert_eq!((image.width(), image.height(), scale), (16, 16, 2.0));
+
+        // Invalid factors keep the original size
+        let config = PreprocessConfig {
+            upscale: 0.0,
+            ..PreprocessConfig::none()
+        };
+        let (image, scale) = config.apply(&gray(255, 0));
+        assert_eq!((image.width(), image.height(), scale), (8, 8, 1.0));
+    }
+
+    #[test]
+    fn inverts_only_dark_images() {
+        let config = PreprocessConfig {
+            invert: Inversion::Auto,
+            ..PreprocessConfig::none()
+        };
+
+        let (dark, _) = config.apply(&gray(20, 240));
+        assert_eq!((luma(&dark, 0, 0), luma(&dark, 1, 1)), (235, 15));
+
+        let (light, _) = config.apply(&gray(240, 20));
+        assert_eq!((luma(&light, 0, 0), luma(&light, 1, 1)), (240, 20));
+    }
+
+    #[test]
+    fn stretches_contrast_to_the_full_range() {
+        let mut image = GrayImage::new(16, 16);
+        for (x, _, Luma([p])) in image.enumerate_pixels_mut() {
+            *p = 100 + x as u8 * 3;
+        }
+        let config = PreprocessConfig {
+            invert: Inversion::Never,
+            ..PreprocessConfig::default()
+        };
+
+        let (image, _) = config.apply(&DynamicImage::ImageLuma8(image));
+        assert_eq!(luma(&image, 0, 0), 0);
+        assert_eq!(luma(&image, 15, 0), 255);
+        assert!((100..160).contains(&luma(&image, 8, 0)));
+    }
+
+    #[test]
+    fn sharpens_edges() {
+        let mut image = GrayImage::from_pixel(16, 16, Luma([100]));
+        for y in 0..16 {
+            for x in 8..16 {
+                image.put_pixel(x, y, Luma([150]));
+            }
+        }
+        let config = PreprocessConfig {
+            sharpen: true,
+            ..PreprocessConfig::none()
+        };
+
+        let (image, _) = config.apply(&DynamicImage::ImageLuma8(image));
+        // Each side of the edge moves away from the other; flat areas are kept
+        assert!(luma(&image, 7, 8) < 100);
+        assert!(luma(&image, 8, 8) > 150);
+        assert_eq!(luma(&image, 0, 8), 100);
+    }
+
+    #[test]
+    fn binarises_with_otsu_threshold() {
+        let mut image = GrayImage::from_pixel(8, 8, Luma([180]));
+        image.put_pixel(0, 0, Luma([60]));
+        image.put_pixel(1, 0, Luma([90]));
+        let config = PreprocessConfig {
+            binarize: true,
+            invert: Inversion::Never,
+            normalize_contrast: false,
+            ..PreprocessConfig::none()
+        };
+
+        let (image, _) = config.apply(&DynamicImage::ImageLuma8(image));
+        assert_eq!(
+            [luma(&image, 0, 0), luma(&image, 1, 0), luma(&image, 2, 0)],
+            [0, 0, 255]
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Tesseract OCR backend
+//!
+//! Runs the `tesseract` command line tool, which must be installed separately, and
+//! reads its TSV output.
+
+use crate::error::CredentialDetectorError;
+use crate::import::parse_tesseract_tsv;
+use crate::ocr::{TextExtractor, TextLine};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Tesseract OCR engine
+#[derive(Debug, Clone)]
+pub struct TesseractEngine {
+    /// Path or name of the tesseract executable
+    command: String,
+    /// Recognition languages (e.g. `eng`, `eng+jpn`)
+    language: String,
+    /// Page segmentation mode (`--psm`)
+    page_segmentation_mode: Option<u8>,
+}
+
+impl Default for TesseractEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TesseractEngine {
+    /// Create a new Tesseract engine using `tesseract` from `PATH` and English
+    pub fn new() -> Self {
+        Self {
+            command: "tesseract".to_string(),
+            language: "eng".to_string(),
+            page_segmentation_mode: None,
+        }
+    }
+
+    /// Set the path of the tesseract executable
+    pub fn with_command(mut self, command: &str) -> Self {
+        self.command = command.to_string();
+        self
+    }
+
+    /// Set the recognition languages (e.g. `eng+jpn`)
+    pub fn with_language(mut self, language: &str) -> Self {
+        self.language = language.to_string();
+        self
+    }
+
+    /// Set the page segmentation mode (e.g. 11 for sparse text in screenshots)
+    pub fn with_page_segmentation_mode(mut self, mode: u8) -> Self {
+        self.page_segmentation_mode = Some(mode);
+        self
+    }
+}
+
+impl TextExtractor for TesseractEngine {
+    fn extract_text(&self, image_data: &[u8]) -> Result<Vec<TextLine>, CredentialDetectorError> {
+        let mut command = Command::new(&self.command);
+        command.args(["stdin", "stdout", "-l", &self.language]);
+        if let Some(mode) = self.page_segmentation_mode {
+            command.args(["--psm", &mode.to_string()]);
+        }
+        command
+            .arg("tsv")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                return Err(CredentialDetectorError::OcrEngineInitError(format!(
+                    "Failed to run {}: {}",
+                    self.command, e
+                )));
+            }
+        };
+
+        // Write the image from another thread so that a full stdout pipe cannot block us
+        let mut stdin = child.stdin.take().unwrap();
+        let output = std::thread::scope(|scope| {
+            scope.spawn(move || stdin.write_all(image_data));
+            child.wait_with_output()
+        });
+
+        let output = match output {
+            Ok(output) => output,
+            Err(e) => return Err(CredentialDetectorError::OcrProcessError(e.to_string())),
+        };
+        if !output.status.success() {
+            return Err(CredentialDetectorError::OcrProcessError(format!(
+                "tesseract failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        parse_tesseract_tsv(&String::from_utf8_lossy(&output.stdout))
+    }
+}
@@ -0,0 +1,243 @@
+//! Tiled OCR of large images
+//!
+//! Full-page screenshots from high-DPI displays and long scrolling captures are split
+//! into overlapping tiles. Text lines found in each tile are moved back to full-image
+//! coordinates and the duplicates found in the overlaps are merged.
+
+use crate::ocr::TextLine;
+
+/// Tiling configuration
+#[derive(Debug, Clone)]
+pub struct TilingConfig {
+    /// Maximum width and height of a tile in pixels
+    pub tile_size: u32,
+    /// Number of pixels shared by neighbouring tiles
+    ///
+    /// Should be larger than the height of a text line so that every line is complete
+    /// in at least one tile.
+    pub overlap: u32,
+}
+
+impl Default for TilingConfig {
+    fn default() -> Self {
+        Self {
+            tile_size: 1600,
+            overlap: 200,
+        }
+    }
+}
+
+/// Region of the image processed as one tile
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Tile {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl TilingConfig {
+    /// Create a new tiling configuration
+    pub fn new(tile_size: u32, overlap: u32) -> Self {
+        Self { tile_size, overlap }
+    }
+
+    /// Determine if an image is large enough to be tiled
+    pub(crate) fn applies_to(&self, width: u32, height: u32) -> bool {
+        width > self.tile_size || height > self.tile_size
+    }
+
+    /// Start offsets of the tiles along one axis
+    fn offsets(&self, length: u32) -> Vec<u32> {
+        let tile_size = self.tile_size.max(1);
+        if length <= tile_size {
+            return vec![0];
+        }
+
+        // Overlaps of a tile size or more would never advance
+        let step = tile_size.saturating_sub(self.overlap).max(1);
+        let mut offsets: Vec<u32> = (0..length - tile_size).step_by(step as usize).collect();
+        offsets.push(length - tile_size);
+        offsets
+    }
+
+    /// Split an image into overlapping tiles covering all of it
+    pub(crate) fn tiles(&self, width: u32, height: u32) -> Vec<Tile> {
+        let xs = self.offsets(width);
+        let ys = self.offsets(height);
+
+        ys.iter()
+            .flat_map(|&y| {
+                xs.iter().map(move |&x| Tile {
+                    x,
+                    y,
+                    width: self.tile_size.min(width),
+                    height: self.tile_size.min(height),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Length of the overlap between two ranges
+fn overlap_length(a_start: f32, a_length: f32, b_start: f32, b_length: f32) -> f32 {
+    ((a_start + a_length).min(b_start + b_length) - a_start.max(b_start)).max(0.0)
+}
+
+/// Join the texts of two fragments of a line cut by a tile seam, `left` coming first
+fn join_fragments(left: &str, right: &str) -> String {
+    if left.contains(right) {
+        return left.to_string();
+    }
+    if right.contains(left) {
+        return right.to_string();
+    }
+
+    // Both tiles read the characters in the overlap, so the end of `left` repeats the
+    // start of `right`
+    let left_chars: Vec<char> = left.chars().collect();
+    let right_chars: Vec<char> = right.chars().collect();
+    let shared = (3..=left_chars.len().min(right_chars.len()))
+        .rev()
+        .find(|&length| left_chars[left_chars.len() - length..] == right_chars[..length]);
+
+    match shared {
+        Some(length) => left_chars.iter().chain(&right_chars[length..]).collect(),
+        None => format!("{} {}", left, right),
+    }
+}
+
+/// Merge the duplicates and fragments of text lines read from overlapping tiles
+///
+/// Lines on the same row whose boxes overlap are either the same line read twice (one
+/// box containing the other, in which case the larger read wins) or two fragments of
+/// a line crossing a seam (which are joined).
+pub(crate) fn merge_tile_lines(mut lines: Vec<TextLine>) -> Vec<TextLine> {
+    lines.sort_by(|a, b| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+
+    let mut merged: Vec<TextLine> = Vec::new();
+    for line in lines {
+        let duplicate = merged.iter_mut().find(|kept| {
+            let vertical = overlap_length(kept.y, kept.height, line.y, line.height);
+            let horizontal = overlap_length(kept.x, kept.width, line.x, line.width);
+            vertical >= 0.5 * kept.height.min(line.height) && horizontal > 0.0
+        });
+
+        let Some(kept) = duplicate else {
+            merged.push(line);
+            continue;
+        };
+
+        let intersection = overlap_length(kept.x, kept.width, line.x, line.width)
+            * overlap_length(kept.y, kept.height, line.y, line.height);
+        let kept_area = kept.width * kept.height;
+        let line_area = line.width * line.height;
+
+        if intersection >= 0.8 * kept_area.min(line_area) {
+            // Same line read twice; a line cut by a seam has a smaller box
+            if line_area > kept_area {
+                *kept = line;
+            }
+            continue;
+        }
+
+        let (left, right) = if kept.x <= line.x {
+            (&*kept, &line)
+        } else {
+            (&line, &*kept)
+        };
+        let text = join_fragments(&left.text, &right.text);
+
+        let x = kept.x.min(line.x);
+        let y = kept.y.min(line.y);
+        let right_edge = (kept.x + kept.width).max(line.x + line.width);
+        let bottom_edge = (kept.y + kept.height).max(line.y + line.height);
+
+        *kept = TextLine {
+            text,
+            x,
+            y,
+            width: right_edge - x,
+            height: bottom_edge - y,
+            score: kept.score.min(line.score),
+        };
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(text: &str, x: f32, y: f32, width: f32) -> TextLine {
+        TextLine {
+            x,
+            y,
+            width,
+            height: 20.0,
+            ..TextLine::test_line(text)
+        }
+    }
+
+    #[test]
+    fn offsets_cover_the_axis() {
+        let config = TilingConfig::new(1000, 200);
+
+        assert_eq!(config.offsets(800), [0]);
+        assert_eq!(config.offsets(1000), [0]);
+        assert_eq!(config.offsets(2500), [0, 800, 1500]);
+        // The last tile ends on the image edge
+        assert_eq!(config.offsets(1700), [0, 700]);
+    }
+
+    #[test]
+    fn offsets_advance_with_large_overlaps() {
+        let config = TilingConfig::new(10, 50);
+        assert_eq!(config.offsets(13), [0, 1, 2, 3]);
+
+        let config = TilingConfig::new(0, 0);
+        assert_eq!(config.offsets(2), [0, 1]);
+    }
+
+    #[test]
+    fn tiles_form_a_grid() {
+        let config = TilingConfig::new(1000, 200);
+        let tiles = config.tiles(2500, 900);
+
+        assert_eq!(tiles.len(), 3);
+        assert!(tiles.iter().all(|tile| tile.y == 0 && tile.height == 900));
+        assert!(tiles.iter().all(|tile| tile.width == 1000));
+        assert_eq!(tiles[2].x, 1500);
+        assert!(!config.applies_to(1000, 900));
+        assert!(config.applies_to(2500, 900));
+    }
+
+    #[test]
+    fn joins_fragments_on_shared_characters() {
+        assert_eq!(
+            join_fragments("API_KEY=abc12", "abc123xyz"),
+            "API_KEY=abc123xyz"
+        );
+        assert_eq!(join_fragments("password", "word"), "password");
+        assert_eq!(join_fragments("left", "right"), "left right");
+    }
+
+    #[test]
+    fn merges_duplicates_and_fragments() {
+        let merged = merge_tile_lines(vec![
+            // The same line read by two tiles, one of them cutting it
+            line("token=abcdef", 0.0, 0.0, 300.0),
+            line("token=abc", 0.0, 2.0, 200.0),
+            // A line crossing a seam
+            line("secret=012345", 0.0, 100.0, 300.0),
+            line("2345678", 200.0, 101.0, 200.0),
+            // A separate line
+            line("other", 0.0, 300.0, 100.0),
+        ]);
+
+        let texts: Vec<&str> = merged.iter().map(|line| line.text.as_str()).collect();
+        assert_eq!(texts, ["token=abcdef", "secret=012345678", "other"]);
+        assert_eq!(merged[1].width, 400.0);
+    }
+}
@@ -0,0 +1,206 @@
+//! Raw pixel buffers
+//!
+//! Screen-capture pipelines hold raw frames; wrapping them avoids an encode/decode
+//! round trip through PNG.
+
+use crate::error::CredentialDetectorError;
+use image::{DynamicImage, GrayImage, RgbImage, RgbaImage};
+
+/// Layout of the pixels in a raw buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// 8-bit red, green, blue, alpha
+    Rgba8,
+    /// 8-bit blue, green, red, alpha (Windows and macOS screen captures)
+    Bgra8,
+    /// 8-bit red, green, blue
+    Rgb8,
+    /// 8-bit blue, green, red
+    Bgr8,
+    /// 8-bit grayscale
+    Gray8,
+}
+
+impl PixelFormat {
+    /// Number of bytes per pixel
+    pub fn bytes_per_pixel(&self) -> usize {
+        match self {
+            PixelFormat::Rgba8 | PixelFormat::Bgra8 => 4,
+            PixelFormat::Rgb8 | PixelFormat::Bgr8 => 3,
+            PixelFormat::Gray8 => 1,
+        }
+    }
+}
+
+/// Borrowed raw pixel buffer
+#[derive(Debug, Clone, Copy)]
+pub struct RawImage<'a> {
+    /// Pixel data, row by row
+    pub data: &'a [u8],
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// Number of bytes from the start of a row to the start of the next one
+    pub stride: usize,
+    /// Layout of the pixels
+    pub format: PixelFormat,
+}
+
+impl<'a> RawImage<'a> {
+    /// Create a new raw image with tightly packed rows
+    pub fn new(data: &'a [u8], width: u32, height: u32, format: PixelFormat) -> Self {
+        Self {
+            data,
+            width,
+            height,
+            stride: width as usize * format.bytes_per_pixel(),
+            format,
+        }
+    }
+
+    /// Set the row stride, for buffers with padding at the end of each row
+    pub fn with_stride(mut self, stride: usize) -> Self {
+        self.stride = stride;
+        self
+    }
+
+    /// Convert the buffer into a decoded image
+    pub fn to_image(&self) -> Result<DynamicImage, CredentialDetectorError> {
+        let bytes_per_pixel = self.format.bytes_per_pixel();
+        let row_length = self.width as usize * bytes_per_pixel;
+
+        if self.width == 0 || self.height == 0 {
+            return Err(CredentialDetectorError::ImageLoadError(
+                "Raw image is empty".to_string(),
+            ));
+        }
+        if self.stride < row_length {
+            return Err(CredentialDetectorError::ImageLoadError(format!(
+                "Stride {} is smaller than the row length {}",
+                self.stride, row_length
+            )));
+        }
+        // Saturate, so that a huge stride or height is reported rather than overflowing
+        let required = self
+            .stride
+            .saturating_mul(self.height as usize - 1)
+            .saturating_add(row_length);
+        if self.data.len() < required {
+            return Err(CredentialDetectorError::ImageLoadError(format!(
+                "Raw image needs {} bytes but {} were given",
+                required,
+                self.data.len()
+            )));
+        }
+
+        // Copy the rows without padding, swapping blue and red where needed
+        let mut pixels = Vec::with_capacity(row_length * self.height as usize);
+        for row in self.data.chunks(self.stride).take(self.height as usize) {
+            let row = &row[..row_length];
+            match self.format {
+                PixelFormat::Bgra8 | PixelFormat::Bgr8 => {
+                    for pixel in row.chunks_exact(bytes_per_pixel) {
+                        pixels.extend([pixel[2], pixel[1], pixel[0]]);
+                        pixels.extend(&pixel[3..]);
+                    }
+                }
+                _ => pixels.extend_from_slice(row),
+            }
+        }
+
+        let (width, height) = (self.width, self.height);
+        let image = match self.format {
+            PixelFormat::Rgba8 | PixelFormat::Bgra8 => {
+                RgbaImage::from_raw(width, height, pixels).map(DynamicImage::ImageRgba8)
+            }
+            PixelFormat::Rgb8 | PixelFormat::Bgr8 => {
+                RgbImage::from_raw(width, height, pixels).map(DynamicImage::ImageRgb8)
+            }
+            PixelFormat::Gray8 => {
+                GrayImage::from_raw(width, height, pixels).map(DynamicImage::ImageLuma8)
+            }
+        };
+
+        image.ok_or_else(|| {
+            CredentialDetectorError::ImageLoadError("Invalid raw image buffer".to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_row_padding() {
+        // 2x2 gray image with two bytes of padding per row; the last row is unpadded
+        let data = [1, 2, 0xee, 0xee, 3, 4];
+        let image = RawImage::new(&data, 2, 2, PixelFormat::Gray8)
+            .with_stride(4)
+            .to_image()
+            .unwrap();
+
+        assert_eq!(image.to_luma8().into_raw(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn swaps_blue_and_red() {
+        let bgra = [10, 20, 30, 40, 50, 60, 70, 80];
+        let image = RawImage::new(&bgra, 2, 1, PixelFormat::Bgra8)
+            .to_image()
+            .unwrap();
+        assert_eq!(
+            image.to_rgba8().into_raw(),
+            [30, 20, 10, 40, 70, 60, 50, 80]
+        );
+
+        let bgr = [10, 20, 30];
+        let image = RawImage::new(&bgr, 1, 1, PixelFormat::Bgr8)
+            .to_image()
+            .unwrap();
+        assert_eq!(image.to_rgb8().into_raw(), [30, 20, 10]);
+
+        let rgba = [10, 20, 30, 40];
+        let image = RawImage::new(&rgba, 1, 1, PixelFormat::Rgba8)
+            .to_image()
+            .unwrap();
+        assert_eq!(image.to_rgba8().into_raw(), rgba);
+    }
+
+    #[test]
+    fn rejects_buffers_that_do_not_fit_the_layout() {
+        let data = [0; 16];
+
+        // Too short for the rows
+        assert!(
+            RawImage::new(&data, 3, 2, PixelFormat::Rgb8)
+                .to_image()
+                .is_err()
+        );
+        assert!(
+            RawImage::new(&data, 2, 2, PixelFormat::Rgba8)
+                .with_stride(12)
+                .to_image()
+                .is_err()
+        );
+        // Stride shorter than a row, or too large to address
+        assert!(
+            RawImage::new(&data, 2, 2, PixelFormat::Rgba8)
+                .with_stride(4)
+                .to_image()
+                .is_err()
+        );
+        assert!(
+            RawImage::new(&data, 1, 3, PixelFormat::Gray8)
+                .with_stride(usize::MAX)
+                .to_image()
+                .is_err()
+        );
+        assert!(
+            RawImage::new(&data, 0, 2, PixelFormat::Gray8)
+                .to_image()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,857 @@
+//! Local HTTP scanning service
+//!
+//! - `POST /scan` scans the request body: an image, or any format
+//!   [`CredentialDetector::detect_from_path`] accepts when named by the `name` query
+//!   parameter (e.g. `?name=deck.pptx`). A `multipart/form-data` body scans each file,
+//!   and fails only when none of them could be scanned.
+//!   With `?redact=true`, images come back with the detections blacked out, as a
+//!   base64 PNG.
+//! - `GET /health` reports that the service is up.
+//! - `GET /metrics` exposes counters in the Prometheus text format.
+
+use crate::detector::CredentialDetector;
+use crate::error::CredentialDetectorError;
+use crate::types::{CredentialDetection, DetectorCategory, ScanReport, Severity};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use image::{DynamicImage, ImageOutputFormat, Rgba};
+use serde_json::{Value, json};
+use std::io::{Cursor, Read};
+use std::net::SocketAddr;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::thread;
+use std::time::Instant;
+use tiny_http::{Header, Method, Request, Response};
+
+/// Configuration of the HTTP scanning service
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// Address to listen on (`host:port`; port 0 picks a free port)
+    pub address: String,
+    /// Requests with a larger body (in bytes) are rejected with 413
+    pub max_body_size: usize,
+    /// Scan requests beyond this many at once are rejected with 503 (at least 1)
+    pub max_concurrent_scans: usize,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            address: "127.0.0.1:8080".to_string(),
+            max_body_size: 32 << 20,
+            max_concurrent_scans: thread::available_parallelism()
+                .map_or(1, |threads| threads.get()),
+        }
+    }
+}
+
+/// Counters exposed by `GET /metrics`
+#[derive(Default)]
+struct Metrics {
+    requests: AtomicU64,
+    scanned_files: AtomicU64,
+    scan_errors: AtomicU64,
+    detections: AtomicU64,
+    rejected_too_large: AtomicU64,
+    rejected_busy: AtomicU64,
+    scans_in_flight: AtomicUsize,
+    scan_microseconds: AtomicU64,
+}
+
+impl Metrics {
+    /// Render the counters in the Prometheus text exposition format
+    fn render(&self) -> String {
+        let counters = [
+            (
+                "requests_total",
+                "HTTP requests received",
+                self.requests.load(Ordering::Relaxed),
+            ),
+            (
+                "scanned_files_total",
+                "Files scanned",
+                self.scanned_files.load(Ordering::Relaxed),
+            ),
+            (
+                "scan_errors_total",
+                "Files that failed to scan",
+                self.scan_errors.load(Ordering::Relaxed),
+            ),
+            (
+                "detections_total",
+                "Credentials detected",
+                self.detections.load(Ordering::Relaxed),
+            ),
+        ];
+
+        let mut text = String::new();
+        for (name, help, value) in counters {
+            text += &format!(
+                "# HELP ocr_credential_detector_{name} {help}\n\
+                 # TYPE ocr_credential_detector_{name} counter\n\
+                 ocr_credential_detector_{name} {value}\n"
+            );
+        }
+        text += &format!(
+            "# HELP ocr_credential_detector_rejected_requests_total Scan requests rejected\n\
+             # TYPE ocr_credential_detector_rejected_requests_total counter\n\
+             ocr_credential_detector_rejected_requests_total{{reason=\"too_large\"}} {}\n\
+             ocr_credential_detector_rejected_requests_total{{reason=\"busy\"}} {}\n",
+            self.rejected_too_large.load(Ordering::Relaxed),
+            self.rejected_busy.load(Ordering::Relaxed),
+        );
+        text += &format!(
+            "# HELP ocr_credential_detector_scans_in_flight Scan requests being processed\n\
+             # TYPE ocr_credential_detector_scans_in_flight gauge\n\
+             ocr_credential_detector_scans_in_flight {}\n",
+            self.scans_in_flight.load(Ordering::Relaxed),
+        );
+        text += &format!(
+            "# HELP ocr_credential_detector_scan_seconds_total Time spent scanning files\n\
+             # TYPE ocr_credential_detector_scan_seconds_total counter\n\
+             ocr_credential_detector_scan_seconds_total {:.6}\n",
+            self.scan_microseconds.load(Ordering::Relaxed) as f64 / 1e6,
+        );
+        text
+    }
+}
+
+/// Releases a scan slot when dropped
+struct ScanSlot<'a>(&'a AtomicUsize);
+
+impl Drop for ScanSlot<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// HTTP scanning service sharing one detector between its worker threads
+pub struct Server {
+    http: tiny_http::Server,
+    detector: Arc<CredentialDetector>,
+    config: ServerConfig,
+    metrics: Metrics,
+}
+
+impl Server {
+    /// Bind the service to the configured address
+    pub fn bind(
+        detector: Arc<CredentialDetector>,
+        config: ServerConfig,
+    ) -> Result<Self, CredentialDetectorError> {
+        if config.max_concurrent_scans == 0 {
+            return Err(CredentialDetectorError::ServerError(
+                "max_concurrent_scans must be at least 1".to_string(),
+            ));
+        }
+        let http = match tiny_http::Server::http(&config.address) {
+            Ok(http) => http,
+            Err(e) => return Err(CredentialDetectorError::ServerError(e.to_string())),
+        };
+
+        Ok(Self {
+            http,
+            detector,
+            config,
+            metrics: Metrics::default(),
+        })
+    }
+
+    /// Address the service is listening on
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.http.server_addr().to_ip()
+    }
+
+    /// Serve requests until the process exits
+    ///
+    /// One worker thread per concurrent scan handles requests, plus one so that
+    /// `/health` and `/metrics` answer while every scan slot is busy.
+    pub fn run(self) {
+        let server = Arc::new(self);
+        let workers: Vec<_> = (0..server.config.max_concurrent_scans + 1)
+            .map(|_| {
+                let server = Arc::clone(&server);
+                thread::spawn(move || {
+                    while let Ok(request) = server.http.recv() {
+                        // Scans report their own panics; a request dropped by any other
+                        // panic is answered with an empty 500, and the worker goes on
+                        let handle = AssertUnwindSafe(|| server.handle(request));
+                        let _ = panic::catch_unwind(handle);
+                    }
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            let _ = worker.join();
+        }
+    }
+
+    /// Route a request
+    fn handle(&self, request: Request) {
+        self.metrics.requests.fetch_add(1, Ordering::Relaxed);
+
+        let url = request.url().to_string();
+        let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+        match (request.method(), path) {
+            (Method::Get, "/health") => {
+                respond_json(request, 200, &json!({ "status": "ok" }));
+            }
+            (Method::Get, "/metrics") => {
+                let body = self.metrics.render();
+                respond(request, 200, "text/plain; version=0.0.4", body.into_bytes());
+            }
+            (Method::Post, "/scan") => {
+                let query = query.to_string();
+                self.scan(request, &query);
+            }
+            (_, "/health" | "/metrics" | "/scan") => {
+                respond_json(request, 405, &json!({ "error": "Method not allowed" }));
+            }
+            _ => respond_json(request, 404, &json!({ "error": "Not found" })),
+        }
+    }
+
+    /// Handle `POST /scan`
+    fn scan(&self, mut request: Request, query: &str) {
+        if request
+            .body_length()
+            .is_some_and(|length| length > self.config.max_body_size)
+        {
+            self.metrics
+                .rejected_too_large
+                .fetch_add(1, Ordering::Relaxed);
+            respond_json(request, 413, &json!({ "error": "Request body too large" }));
+            return;
+        }
+
+        // Reject rather than queue, so that callers can back off or try another replica
+        let in_flight = self.metrics.scans_in_flight.fetch_add(1, Ordering::Relaxed);
+        let slot = ScanSlot(&self.metrics.scans_in_flight);
+        if in_flight >= self.config.max_concurrent_scans {
+            self.metrics.rejected_busy.fetch_add(1, Ordering::Relaxed);
+            let body = json!({ "error": "Too many concurrent scans" }).to_string();
+            let mut response = Response::from_string(body).with_status_code(503);
+            for (field, value) in [("Content-Type", "application/json"), ("Retry-After", "1")] {
+                if let Ok(header) = Header::from_bytes(field, value) {
+                    response.add_header(header);
+                }
+            }
+            drop(slot);
+            let _ = request.respond(response);
+            return;
+        }
+
+        let (status, response) = self.scan_body(&mut request, query);
+        // Free the slot before answering, so that the client's next scan is not rejected
+        drop(slot);
+        respond_json(request, status, &response);
+    }
+
+    /// Read and scan the body of a `POST /scan`, returning the HTTP status and response
+    fn scan_body(&self, request: &mut Request, query: &str) -> (u16, Value) {
+        // Chunked bodies have no declared length, so the limit is enforced while reading
+        let mut body = Vec::new();
+        let limit = self.config.max_body_size as u64;
+        if let Err(e) = request.as_reader().take(limit + 1).read_to_end(&mut body) {
+            return (400, json!({ "error": e.to_string() }));
+        }
+        if body.len() as u64 > limit {
+            self.metrics
+                .rejected_too_large
+                .fetch_add(1, Ordering::Relaxed);
+            return (413, json!({ "error": "Request body too large" }));
+        }
+
+        let redact =
+            query_parameter(query, "redact").is_some_and(|value| value == "true" || value == "1");
+        let boundary = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Content-Type"))
+            .and_then(|header| multipart_boundary(header.value.as_str()));
+
+        match boundary {
+            Some(boundary) => {
+                let (statuses, files): (Vec<u16>, Vec<Value>) = parse_multipart(&body, &boundary)
+                    .into_iter()
+                    .map(|(name, data)| self.scan_file(&name, data, redact))
+                    .unzip();
+                // Succeed when any file could be scanned; otherwise report the worst failure
+                let status = if statuses.contains(&200) {
+                    200
+                } else {
+                    match statuses.into_iter().max() {
+                        Some(status) => status,
+                        None => return (400, json!({ "error": "No files in multipart body" })),
+                    }
+                };
+                (status, json!({ "files": files }))
+            }
+            None => {
+                let name = query_parameter(query, "name").unwrap_or_else(|| "upload".to_string());
+                self.scan_file(&name, &body, redact)
+            }
+        }
+    }
+
+    /// Scan one file, returning the HTTP status for it and its JSON report
+    ///
+    /// A file that cannot be scanned gives 422, and one whose scan panicked 500.
+    fn scan_file(&self, name: &str, data: &[u8], redact: bool) -> (u16, Value) {
+        let started = Instant::now();
+        let scan = AssertUnwindSafe(|| self.detector.scan_file_data(data, name));
+        let result = match panic::catch_unwind(scan) {
+            Ok(result) => result.map_err(|e| (422, e)),
+            Err(_) => Err((
+                500,
+                CredentialDetectorError::UnknownError(format!("Scanning {} panicked", name)),
+            )),
+        };
+        self.metrics
+            .scan_microseconds
+            .fetch_add(started.elapsed().as_micros() as u64, Ordering::Relaxed);
+        self.metrics.scanned_files.fetch_add(1, Ordering::Relaxed);
+
+        let ScanReport {
+            detections,
+            skipped,
+        } = match result {
+            Ok(report) => report,
+            Err((status, e)) => {
+                self.metrics.scan_errors.fetch_add(1, Ordering::Relaxed);
+                return (status, json!({ "name": name, "error": e.to_string() }));
+            }
+        };
+        self.metrics
+            .detections
+            .fetch_add(detections.len() as u64, Ordering::Relaxed);
+
+        let mut file = json!({
+            "name": name,
+            "detections": detections.iter().map(detection_json).collect::<Vec<_>>(),
+            "skipped": skipped.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        });
+        if redact && let Some(redacted) = redact_image(data, &detections) {
+            file["redacted_image"] = Value::String(STANDARD.encode(redacted));
+        }
+        (200, file)
+    }
+}
+
+/// JSON representation of a detection
+fn detection_json(detection: &CredentialDetection) -> Value {
+    let bounding_box = &detection.bounding_box;
+    json!({
+        "text": detection.text,
+        "rule_id": detection.rule_id,
+        "detector_name": detection.detector_name,
+        "description": detection.description,
+        "severity": severity_name(detection.severity),
+        "category": category_name(detection.category),
+        "credential_score": detection.credential_score,
+        "text_detection_score": detection.text_detection_score,
+        "bounding_box": {
+            "x": bounding_box.x,
+            "y": bounding_box.y,
+            "width": bounding_box.width,
+            "height": bounding_box.height,
+        },
+        "remediation_url": detection.remediation_url,
+        "metadata": detection.metadata,
+    })
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Low => "low",
+        Severity::Medium => "medium",
+        Severity::High => "high",
+        Severity::Critical => "critical",
+    }
+}
+
+fn category_name(category: DetectorCategory) -> &'static str {
+    match category {
+        DetectorCategory::CloudKey => "cloud_key",
+        DetectorCategory::Token => "token",
+        DetectorCategory::Password => "password",
+        DetectorCategory::PrivateKey => "private_key",
+        DetectorCategory::PaymentCard => "payment_card",
+        DetectorCategory::Pii => "pii",
+        DetectorCategory::GenericSecret => "generic_secret",
+    }
+}
+
+/// Black out the detections on an image, returning it as PNG
+///
+/// Returns `None` for data that is not a single image (documents, archives, video),
+/// whose detections are not in image coordinates.
+fn redact_image(data: &[u8], detections: &[CredentialDetection]) -> Option<Vec<u8>> {
+    let mut image = image::load_from_memory(data).ok()?.to_rgba8();
+    for detection in detections {
+        let bounding_box = &detection.bounding_box;
+        // Boxes within a line assume evenly spaced characters; with proportional fonts
+        // the secret can extend past them, so pad by about two characters each side
+        let padding = bounding_box.height;
+        let left = (bounding_box.x - padding).max(0.0) as u32;
+        let top = (bounding_box.y - padding / 4.0).max(0.0) as u32;
+        let right = ((bounding_box.x + bounding_box.width + padding)
+            .ceil()
+            .max(0.0) as u32)
+            .min(image.width());
+        let bottom = ((bounding_box.y + bounding_box.height + padding / 4.0)
+            .ceil()
+            .max(0.0) as u32)
+            .min(image.height());
+        for y in top..bottom {
+            for x in left..right {
+                image.put_pixel(x, y, Rgba([0, 0, 0, 255]));
+            }
+        }
+    }
+
+    let mut png = Vec::new();
+    DynamicImage::ImageRgba8(image)
+        .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+        .ok()?;
+    Some(png)
+}
+
+/// Value of a query string parameter (percent-decoding is not needed for its values)
+fn query_parameter(query: &str, name: &str) -> Option<String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+}
+
+/// Boundary of a `multipart/form-data` content type
+fn multipart_boundary(content_type: &str) -> Option<String> {
+    let (media_type, parameters) = content_type.split_once(';')?;
+    if !media_type
+        .trim()
+        .eq_ignore_ascii_case("multipart/form-data")
+    {
+        return None;
+    }
+    parameters.split(';').find_map(|parameter| {
+        let (key, value) = parameter.trim().split_once('=')?;
+        key.eq_ignore_ascii_case("boundary")
+            .then(|| value.trim_matches('"').to_string())
+    })
+}
+
+/// Split a `multipart/form-data` body into named files
+fn parse_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<(String, &'a [u8])> {
+    // Delimiters start on a new line; the line break belongs to the delimiter, not to
+    // the data before it
+    let delimiter = format!("\r\n--{}", boundary);
+    let parts = match body.strip_prefix(&delimiter.as_bytes()[2..]) {
+        // The first delimiter has no line break before it at the start of the body
+        Some(rest) => split_bytes(rest, delimiter.as_bytes()),
+        // Otherwise everything up to it is a preamble
+        None => split_bytes(body, delimiter.as_bytes()).split_off(1),
+    };
+
+    let mut files = Vec::new();
+    for (index, part) in (1..).zip(parts) {
+        // The closing delimiter is followed by `--`
+        if part.starts_with(b"--") {
+            break;
+        }
+        let part = part.strip_prefix(b"\r\n").unwrap_or(part);
+        let Some(header_end) = find_bytes(part, b"\r\n\r\n") else {
+            continue;
+        };
+        let headers = String::from_utf8_lossy(&part[..header_end]);
+        let data = &part[header_end + 4..];
+
+        let name = headers
+            .lines()
+            .filter(|line| {
+                line.to_ascii_lowercase()
+                    .starts_with("content-disposition:")
+            })
+            .find_map(|line| {
+                let (_, filename) = line.split_once("filename=")?;
+                Some(
+                    filename
+                        .split(';')
+                        .next()?
+                        .trim()
+                        .trim_matches('"')
+                        .to_string(),
+                )
+            })
+            .unwrap_or_else(|| format!("part{}", index));
+        files.push((name, data));
+    }
+    files
+}
+
+/// Position of the first occurrence of a byte string
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Split a byte string on every occurrence of a delimiter
+fn split_bytes<'a>(mut data: &'a [u8], delimiter: &[u8]) -> Vec<&'a [u8]> {
+    let mut parts = Vec::new();
+    while let Some(position) = find_bytes(data, delimiter) {
+        parts.push(&data[..position]);
+        data = &data[position + delimiter.len()..];
+    }
+    parts.push(data);
+    parts
+}
+
+fn respond(request: Request, status: u16, content_type: &str, body: Vec<u8>) {
+    let mut response = Response::from_data(body).with_status_code(status);
+    if let Ok(header) = Header::from_bytes("Content-Type", content_type) {
+        response.add_header(header);
+    }
+    // The client may have gone away; there is nobody left to report to
+    let _ = request.respond(response);
+}
+
+fn respond_json(request: Request, status: u16, body: &Value) {
+    respond(
+        request,
+        status,
+        "application/json",
+        body.to_string().into_bytes(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detectors::PasswordDetector;
+    use crate::ocr::{PrecomputedText, TextLine};
+    use crate::types::BoundingBox;
+    use image::RgbaImage;
+    use std::io::Write;
+    use std::net::TcpStream;
+    use std::sync::atomic::AtomicBool;
+    use std::time::Duration;
+
+    /// Line across the top of the 32 pixel wide test image
+    fn line(text: &str) -> TextLine {
+        TextLine {
+            width: 20.0,
+            ..TextLine::test_line(text)
+        }
+    }
+
+    fn png() -> Vec<u8> {
+        let image = RgbaImage::from_pixel(32, 32, Rgba([255, 255, 255, 255]));
+        let mut data = Vec::new();
+        DynamicImage::ImageRgba8(image)
+            .write_to(&mut Cursor::new(&mut data), ImageOutputFormat::Png)
+            .unwrap();
+        data
+    }
+
+    /// Run a server on a free local port
+    fn start(detector: CredentialDetector, config: ServerConfig) -> SocketAddr {
+        let config = ServerConfig {
+            address: "127.0.0.1:0".to_string(),
+            ..config
+        };
+        let server = Server::bind(Arc::new(detector), config).unwrap();
+        let address = server.local_addr().unwrap();
+        thread::spawn(move || server.run());
+        address
+    }
+
+    fn start_default() -> SocketAddr {
+        // Decode the image, so that invalid uploads fail whether or not barcodes are read
+        let detector = CredentialDetector::from_text_extractor(|image_data: &[u8]| {
+            image::load_from_memory(image_data)
+                .map_err(|e| CredentialDetectorError::ImageLoadError(e.to_string()))?;
+            Ok(vec![line("password: hunter2")])
+        });
+        start(detector, ServerConfig::default())
+    }
+
+    /// Send a request, returning the status code and the body
+    fn send(
+        address: SocketAddr,
+        method: &str,
+        target: &str,
+        headers: &[(&str, &str)],
+        body: &[u8],
+    ) -> (u16, String) {
+        let mut stream = TcpStream::connect(address).unwrap();
+        let mut head = format!(
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n",
+            method,
+            target,
+            body.len()
+        );
+        for (field, value) in headers {
+            head += &format!("{}: {}\r\n", field, value);
+        }
+        stream.write_all(head.as_bytes()).unwrap();
+        stream.write_all(b"\r\n").unwrap();
+        // The server may answer and close before reading a rejected body
+        let _ = stream.write_all(body);
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).unwrap();
+        let response = String::from_utf8_lossy(&response);
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, body.to_string())
+    }
+
+    fn json_body(body: &str) -> Value {
+        serde_json::from_str(body).unwrap()
+    }
+
+    #[test]
+    fn routes_requests() {
+        let address = start_default();
+
+        assert_eq!(send(address, "GET", "/health", &[], b"").0, 200);
+        assert_eq!(send(address, "GET", "/scan", &[], b"").0, 405);
+        assert_eq!(send(address, "POST", "/health", &[], b"").0, 405);
+        assert_eq!(send(address, "GET", "/missing", &[], b"").0, 404);
+    }
+
+    #[test]
+    fn scans_uploads() {
+        let address = start_default();
+
+        let (status, body) = send(address, "POST", "/scan?name=screen.png", &[], &png());
+        assert_eq!(status, 200);
+        let file = json_body(&body);
+        assert_eq!(file["name"], "screen.png");
+        assert_eq!(file["detections"][0]["rule_id"], "key-value-secret");
+        assert_eq!(file["skipped"], json!([]));
+        assert!(file.get("redacted_image").is_none());
+
+        let (status, body) = send(address, "POST", "/scan", &[], b"not an image");
+        assert_eq!(status, 422);
+        assert!(json_body(&body)["error"].is_string());
+    }
+
+    #[test]
+    fn redacts_detections() {
+        let address = start_default();
+
+        let (status, body) = send(address, "POST", "/scan?redact=true", &[], &png());
+        assert_eq!(status, 200);
+        let redacted = STANDARD
+            .decode(json_body(&body)["redacted_image"].as_str().unwrap())
+            .unwrap();
+        let image = image::load_from_memory(&redacted).unwrap().to_rgba8();
+        // Only the line with the detection is blacked out
+        assert!((0..32).any(|x| image.get_pixel(x, 5) == &Rgba([0, 0, 0, 255])));
+        assert!((0..32).all(|x| image.get_pixel(x, 20) == &Rgba([255, 255, 255, 255])));
+    }
+
+    #[test]
+    fn pads_redacted_boxes() {
+        let detector = PasswordDetector::new();
+        let bounding_box = BoundingBox {
+            x: 12.0,
+            y: 12.0,
+            width: 8.0,
+            height: 8.0,
+        };
+        let detection =
+            CredentialDetection::new(&detector, "hunter2".to_string(), bounding_box, 1.0, 1.0);
+
+        let redacted = redact_image(&png(), &[detection]).unwrap();
+        let image = image::load_from_memory(&redacted).unwrap().to_rgba8();
+        let black = |x, y| image.get_pixel(x, y) == &Rgba([0, 0, 0, 255]);
+        // Characters wider than estimated are still covered
+        assert!(black(5, 16) && black(26, 16));
+        assert!(black(16, 10) && black(16, 21));
+        assert!(!black(2, 16) && !black(30, 16) && !black(16, 5) && !black(16, 25));
+    }
+
+    #[test]
+    fn scans_each_multipart_file() {
+        let address = start_default();
+
+        let mut body = Vec::new();
+        for (name, data) in [("a.png", png()), ("b.png", b"not an image".to_vec())] {
+            body.extend_from_slice(
+                format!(
+                    "--xyz\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\n\
+                     Content-Type: image/png\r\n\r\n",
+                    name
+                )
+                .as_bytes(),
+            );
+            body.extend_from_slice(&data);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(b"--xyz--\r\n");
+
+        let content_type = [("Content-Type", "multipart/form-data; boundary=xyz")];
+        let (status, response) = send(address, "POST", "/scan", &content_type, &body);
+        assert_eq!(status, 200);
+        let files = json_body(&response)["files"].as_array().unwrap().clone();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0]["name"], "a.png");
+        assert_eq!(files[0]["detections"].as_array().unwrap().len(), 1);
+        assert_eq!(files[1]["name"], "b.png");
+        assert!(files[1]["error"].is_string());
+    }
+
+    #[test]
+    fn fails_multipart_requests_without_a_scanned_file() {
+        let address = start_default();
+        let content_type = [("Content-Type", "multipart/form-data; boundary=xyz")];
+
+        let body = b"--xyz\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"a.png\"\r\n\r\n\
+            not an image\r\n--xyz--\r\n";
+        let (status, response) = send(address, "POST", "/scan", &content_type, body);
+        assert_eq!(status, 422);
+        assert!(json_body(&response)["files"][0]["error"].is_string());
+
+        let (status, _) = send(address, "POST", "/scan", &content_type, b"--xyz--\r\n");
+        assert_eq!(status, 400);
+    }
+
+    #[test]
+    fn rejects_large_bodies_and_reports_metrics() {
+        let detector = CredentialDetector::from_text_extractor(PrecomputedText::default());
+        let config = ServerConfig {
+            max_body_size: 16,
+            ..ServerConfig::default()
+        };
+        let address = start(detector, config);
+
+        assert_eq!(send(address, "POST", "/scan", &[], &[0; 64]).0, 413);
+
+        let (status, metrics) = send(address, "GET", "/metrics", &[], b"");
+        assert_eq!(status, 200);
+        assert!(metrics.contains("ocr_credential_detector_requests_total 2\n"));
+        assert!(
+            metrics.contains(
+                "ocr_credential_detector_rejected_requests_total{reason=\"too_large\"} 1\n"
+            )
+        );
+        assert!(metrics.contains("ocr_credential_detector_scans_in_flight 0\n"));
+    }
+
+    #[test]
+    fn rejects_scans_beyond_the_limit() {
+        let started = Arc::new(AtomicBool::new(false));
+        let released = Arc::new(AtomicBool::new(false));
+        let detector = CredentialDetector::from_text_extractor({
+            let started = Arc::clone(&started);
+            let released = Arc::clone(&released);
+            move |_: &[u8]| {
+                started.store(true, Ordering::SeqCst);
+                while !released.load(Ordering::SeqCst) {
+                    thread::sleep(Duration::from_millis(5));
+                }
+                Ok(Vec::new())
+            }
+        });
+        let config = ServerConfig {
+            max_concurrent_scans: 1,
+            ..ServerConfig::default()
+        };
+        let address = start(detector, config);
+
+        let first = thread::spawn(move || send(address, "POST", "/scan", &[], &png()).0);
+        while !started.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        assert_eq!(send(address, "POST", "/scan", &[], &png()).0, 503);
+        // Health checks still answer while every scan slot is busy
+        assert_eq!(send(address, "GET", "/health", &[], b"").0, 200);
+
+        released.store(true, Ordering::SeqCst);
+        assert_eq!(first.join().unwrap(), 200);
+    }
+
+    #[test]
+    fn answers_500_when_a_scan_panics() {
+        let detector =
+            CredentialDetector::from_text_extractor(|_: &[u8]| -> Result<Vec<TextLine>, _> {
+                panic!("extractor bug")
+            });
+        let address = start(detector, ServerConfig::default());
+
+        let (status, body) = send(address, "POST", "/scan", &[], &png());
+        assert_eq!(status, 500);
+        assert!(
+            json_body(&body)["error"]
+                .as_str()
+                .unwrap()
+                .contains("panicked")
+        );
+        assert_eq!(send(address, "GET", "/health", &[], b"").0, 200);
+    }
+
+    #[test]
+    fn rejects_zero_concurrent_scans() {
+        let detector = CredentialDetector::from_text_extractor(PrecomputedText::default());
+        let config = ServerConfig {
+            address: "127.0.0.1:0".to_string(),
+            max_concurrent_scans: 0,
+            ..ServerConfig::default()
+        };
+        assert!(Server::bind(Arc::new(detector), config).is_err());
+    }
+
+    #[test]
+    fn reads_multipart_boundaries() {
+        assert_eq!(
+            multipart_boundary("multipart/form-data; boundary=xyz").as_deref(),
+            Some("xyz")
+        );
+        assert_eq!(
+            multipart_boundary("Multipart/Form-Data; charset=utf-8; BOUNDARY=\"a b\"").as_deref(),
+            Some("a b")
+        );
+        assert_eq!(multipart_boundary("multipart/mixed; boundary=xyz"), None);
+        assert_eq!(multipart_boundary("image/png"), None);
+    }
+
+    #[test]
+    fn splits_multipart_bodies() {
+        let body = b"preamble\r\n--xyz\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"a.png\"\r\n\r\n\
+            first\r\n--xyz\r\n\
+            Content-Disposition: form-data; name=\"note\"\r\n\r\n\
+            line one\r\nline two\r\n--xyz\r\n\
+            no header terminator\r\n--xyz--\r\nepilogue";
+
+        let files = parse_multipart(body, "xyz");
+        assert_eq!(
+            files,
+            [
+                ("a.png".to_string(), &b"first"[..]),
+                ("part2".to_string(), &b"line one\r\nline two"[..]),
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_multipart_bodies_on_delimiter_lines() {
+        // The boundary inside the data is not on its own line, and the data ends with
+        // a line break of its own
+        let body = b"--xyz\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\n\r\n\
+            a--xyz b\r\n\r\n--xyz--";
+
+        let files = parse_multipart(body, "xyz");
+        assert_eq!(files, [("a.txt".to_string(), &b"a--xyz b\r\n"[..])]);
+    }
+}
@@ -0,0 +1,496 @@
+//! Archives and office documents
+//!
+//! Walks zip files (including `.docx`, `.pptx`, `.xlsx`, `.odt` and `.epub`, which
+//! are zip containers), tar files and gzip streams, descending into nested archives.
+//! Entries are named by their nested path, e.g. `deck.pptx!/ppt/media/image3.png`.
+
+use crate::error::CredentialDetectorError;
+use flate2::read::GzDecoder;
+use std::io::{Cursor, Read};
+
+/// Limits on archive extraction, guarding against archive bombs
+#[derive(Debug, Clone)]
+pub struct ArchiveLimits {
+    /// Maximum nesting of archives inside archives
+    pub max_depth: usize,
+    /// Entries larger than this (in bytes, uncompressed) are skipped
+    pub max_entry_size: u64,
+    /// Maximum total number of bytes extracted from an archive
+    pub max_total_size: u64,
+    /// Maximum total number of entries read from an archive
+    pub max_entries: usize,
+}
+
+impl Default for ArchiveLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            max_entry_size: 64 << 20,
+            max_total_size: 1 << 30,
+            max_entries: 10_000,
+        }
+    }
+}
+
+/// Kinds of containers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Container {
+    Zip,
+    Tar,
+    Gzip,
+}
+
+impl Container {
+    /// Identify a container from its magic bytes
+    fn detect(data: &[u8]) -> Option<Self> {
+        if data.starts_with(b"PK\x03\x04") || data.starts_with(b"PK\x05\x06") {
+            Some(Container::Zip)
+        } else if data.starts_with(&[0x1f, 0x8b]) {
+            Some(Container::Gzip)
+        } else if data.get(257..262) == Some(b"ustar") {
+            Some(Container::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Determine if data is a zip, tar or gzip archive
+pub(crate) fn is_archive(data: &[u8]) -> bool {
+    Container::detect(data).is_some()
+}
+
+/// Read at most `limit` bytes, returning `None` when there is more
+fn read_limited(reader: impl Read, limit: u64) -> std::io::Result<Option<Vec<u8>>> {
+    let mut data = Vec::new();
+    reader.take(limit + 1).read_to_end(&mut data)?;
+    Ok((data.len() as u64 <= limit).then_some(data))
+}
+
+/// Archive error naming the entry it occurred in
+fn archive_error(path: &str, error: impl std::fmt::Display) -> CredentialDetectorError {
+    CredentialDetectorError::ArchiveError(format!("{}: {}", path, error))
+}
+
+/// Visitor receiving the walker, the nested path and the data of each file in an archive
+///
+/// The visitor can walk archives nested in the file (e.g. in an email attachment) with
+/// the walker it is given, so that they count against the same limits.
+pub(crate) type Visitor<'a, 'v> =
+    dyn FnMut(&mut ArchiveWalker<'a>, &str, &[u8]) -> Result<(), CredentialDetectorError> + 'v;
+
+/// Walks archives within the given limits
+///
+/// An entry that cannot be read, that exceeds a size or nesting limit, or that the
+/// visitor fails on, is skipped and recorded rather than ending the walk; only the
+/// total limits being exceeded does.
+pub(crate) struct ArchiveWalker<'a> {
+    limits: &'a ArchiveLimits,
+    extracted: u64,
+    entries: usize,
+    /// Containers the walk is currently inside
+    depth: usize,
+    skipped: Vec<CredentialDetectorError>,
+}
+
+impl<'a> ArchiveWalker<'a> {
+    pub fn new(limits: &'a ArchiveLimits) -> Self {
+        Self {
+            limits,
+            extracted: 0,
+            entries: 0,
+            depth: 0,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Errors of the entries that were skipped
+    pub fn into_skipped(self) -> Vec<CredentialDetectorError> {
+        self.skipped
+    }
+
+    /// Record an entry that was skipped, such as an email part
+    #[cfg(feature = "email")]
+    pub fn skip(&mut self, error: CredentialDetectorError) {
+        self.skipped.push(error);
+    }
+
+    /// Run `f` one level of nesting down, e.g. inside an archive or an email
+    ///
+    /// Past `max_depth` levels the container named `path` is recorded as skipped and
+    /// `None` is returned.
+    pub fn descend<T>(&mut self, path: &str, f: impl FnOnce(&mut Self) -> T) -> Option<T> {
+        if self.depth > self.limits.max_depth {
+            self.skipped.push(archive_error(path, "nesting too deep"));
+            return None;
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        Some(result)
+    }
+
+    /// Visit every file in an archive named `path`
+    pub fn walk(
+        &mut self,
+        data: &[u8],
+        path: &str,
+        visit: &mut Visitor<'a, '_>,
+    ) -> Result<(), CredentialDetectorError> {
+        if !is_archive(data) {
+            return Err(archive_error(path, "not a zip, tar or gzip archive"));
+        }
+        self.descend(path, |walker| walker.walk_container(data, path, visit))
+            .unwrap_or(Ok(()))
+    }
+
+    fn walk_container(
+        &mut self,
+        data: &[u8],
+        path: &str,
+        visit: &mut Visitor<'a, '_>,
+    ) -> Result<(), CredentialDetectorError> {
+        match Container::detect(data) {
+            Some(Container::Zip) => {
+                let mut archive =
+                    zip::ZipArchive::new(Cursor::new(data)).map_err(|e| archive_error(path, e))?;
+                for index in 0..archive.len() {
+                    let file = match archive.by_index(index) {
+                        Ok(file) => file,
+                        Err(e) => {
+                            self.skipped
+                                .push(archive_error(&format!("{}!/#{}", path, index), e));
+                            continue;
+                        }
+                    };
+                    if file.is_dir() {
+                        continue;
+                    }
+                    let name = match file.name() {
+                        Ok(name) => format!("{}!/{}", path, name),
+                        Err(e) => {
+                            self.skipped
+                                .push(archive_error(&format!("{}!/#{}", path, index), e));
+                            continue;
+                        }
+                    };
+                    if file.size() > self.limits.max_entry_size {
+                        self.skipped.push(archive_error(&name, "entry too large"));
+                        continue;
+                    }
+                    self.read_entry(file, &name, visit)?;
+                }
+                Ok(())
+            }
+            Some(Container::Tar) => {
+                let mut archive = tar::Archive::new(Cursor::new(data));
+                let entries = archive.entries().map_err(|e| archive_error(path, e))?;
+                for file in entries {
+                    // A corrupt header leaves no way to find the next entry
+                    let file = match file {
+                        Ok(file) => file,
+                        Err(e) => {
+                            self.skipped.push(archive_error(path, e));
+                            break;
+                        }
+                    };
+                    if !file.header().entry_type().is_file() {
+                        continue;
+                    }
+                    let name = format!("{}!/{}", path, String::from_utf8_lossy(&file.path_bytes()));
+                    if file.size() > self.limits.max_entry_size {
+                        self.skipped.push(archive_error(&name, "entry too large"));
+                        continue;
+                    }
+                    self.read_entry(file, &name, visit)?;
+                }
+                Ok(())
+            }
+            // Compression is transparent in the path (`bundle.tar.gz!/...`), but still
+            // counts as a level of nesting
+            Some(Container::Gzip) => {
+                let Some(inner) = read_limited(GzDecoder::new(data), self.limits.max_entry_size)
+                    .map_err(|e| archive_error(path, e))?
+                else {
+                    self.skipped.push(archive_error(path, "entry too large"));
+                    return Ok(());
+                };
+                self.count(inner.len(), path)?;
+                match Container::detect(&inner) {
+                    Some(_) => self
+                        .descend(path, |walker| walker.walk_container(&inner, path, visit))
+                        .unwrap_or(Ok(())),
+                    None => self.visit(&inner, path.trim_end_matches(".gz"), visit),
+                }
+            }
+            None => self.visit(data, path, visit),
+        }
+    }
+
+    /// Read an entry of at most `max_entry_size` bytes and visit it
+    fn read_entry(
+        &mut self,
+        file: impl Read,
+        path: &str,
+        visit: &mut Visitor<'a, '_>,
+    ) -> Result<(), CredentialDetectorError> {
+        match read_limited(file, self.limits.max_entry_size) {
+            Ok(Some(entry)) => self.entry(&entry, path, visit),
+            // The declared size can be smaller than the data
+            Ok(None) => {
+                self.skipped.push(archive_error(path, "entry too large"));
+                Ok(())
+            }
+            Err(e) => {
+                self.skipped.push(archive_error(path, e));
+                Ok(())
+            }
+        }
+    }
+
+    /// Visit an extracted entry, descending into nested archives
+    fn entry(
+        &mut self,
+        data: &[u8],
+        path: &str,
+        visit: &mut Visitor<'a, '_>,
+    ) -> Result<(), CredentialDetectorError> {
+        self.count(data.len(), path)?;
+
+        if is_archive(data) {
+            if let Some(Err(e)) =
+                self.descend(path, |walker| walker.walk_container(data, path, visit))
+            {
+                // A corrupt nested archive is skipped like any other entry
+                if self.is_over_limits() {
+                    return Err(e);
+                }
+                self.skipped.push(e);
+            }
+            return Ok(());
+        }
+        self.visit(data, path, visit)
+    }
+
+    /// Visit a file, recording its error if the visitor fails
+    ///
+    /// Fails only when the total limits were exceeded while visiting, e.g. by an archive
+    /// nested in an email.
+    fn visit(
+        &mut self,
+        data: &[u8],
+        path: &str,
+        visit: &mut Visitor<'a, '_>,
+    ) -> Result<(), CredentialDetectorError> {
+        if let Err(e) = visit(self, path, data) {
+            if self.is_over_limits() {
+                return Err(e);
+            }
+            self.skipped.push(e);
+        }
+        Ok(())
+    }
+
+    /// Account for extracted data, failing once a limit is exceeded
+    fn count(&mut self, size: usize, path: &str) -> Result<(), CredentialDetectorError> {
+        self.entries += 1;
+        self.extracted += size as u64;
+
+        if self.entries > self.limits.max_entries {
+            return Err(archive_error(path, "too many entries"));
+        }
+        if self.extracted > self.limits.max_total_size {
+            return Err(archive_error(path, "extracted size limit exceeded"));
+        }
+        Ok(())
+    }
+
+    /// Determine if a limit has been exceeded, which ends the walk
+    fn is_over_limits(&self) -> bool {
+        self.entries > self.limits.max_entries || self.extracted > self.limits.max_total_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+    use zip::write::{SimpleFileOptions, ZipWriter};
+
+    fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, data) in files {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    /// Walk an archive, returning the visited paths and the skipped errors
+    fn walk<'a>(
+        data: &[u8],
+        limits: &'a ArchiveLimits,
+        visit: &mut Visitor<'a, '_>,
+    ) -> Result<Vec<String>, CredentialDetectorError> {
+        let mut walker = ArchiveWalker::new(limits);
+        walker.walk(data, "a.zip", visit)?;
+        Ok(walker
+            .into_skipped()
+            .iter()
+            .map(|e| e.to_string())
+            .collect())
+    }
+
+    #[test]
+    fn names_nested_entries() {
+        let inner = zip(&[("image.png", b"png")]);
+        let outer = zip(&[("inner.zip", &inner), ("notes.txt.gz", &gzip(b"text"))]);
+
+        let mut paths = Vec::new();
+        let skipped = walk(&outer, &ArchiveLimits::default(), &mut |_, path, _| {
+            paths.push(path.to_string());
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(paths, ["a.zip!/inner.zip!/image.png", "a.zip!/notes.txt"]);
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn skips_failing_and_corrupt_entries() {
+        let mut corrupt = zip(&[("image.png", b"png")]);
+        corrupt.truncate(corrupt.len() - 10);
+        let outer = zip(&[
+            ("bad.png", b"bad"),
+            ("corrupt.zip", &corrupt),
+            ("good.png", b"good"),
+        ]);
+
+        let mut paths = Vec::new();
+        let skipped = walk(&outer, &ArchiveLimits::default(), &mut |_, path, data| {
+            if data == b"bad" {
+                return Err(CredentialDetectorError::OcrProcessError(
+                    "failed".to_string(),
+                ));
+            }
+            paths.push(path.to_string());
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(paths, ["a.zip!/good.png"]);
+        assert_eq!(skipped.len(), 2);
+        assert!(skipped[1].contains("a.zip!/corrupt.zip"));
+    }
+
+    #[test]
+    fn counts_gzip_as_a_nesting_level() {
+        let mut data = b"innermost".to_vec();
+        for _ in 0..5 {
+            data = gzip(&data);
+        }
+        let limits = ArchiveLimits {
+            max_depth: 2,
+            ..ArchiveLimits::default()
+        };
+
+        let mut visited = 0;
+        let skipped = walk(&data, &limits, &mut |_, _, _| {
+            visited += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(visited, 0);
+        assert_eq!(
+            skipped,
+            ["Archive extraction failed: a.zip: nesting too deep"]
+        );
+    }
+
+    #[test]
+    fn records_entries_over_the_limits() {
+        let outer = zip(&[
+            ("large.png", &[0; 100]),
+            ("large.txt.gz", &gzip(&[0; 100])),
+            ("small.png", b"png"),
+        ]);
+        let limits = ArchiveLimits {
+            max_entry_size: 50,
+            ..ArchiveLimits::default()
+        };
+
+        let mut paths = Vec::new();
+        let skipped = walk(&outer, &limits, &mut |_, path, _| {
+            paths.push(path.to_string());
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(paths, ["a.zip!/small.png"]);
+        assert_eq!(
+            skipped,
+            [
+                "Archive extraction failed: a.zip!/large.png: entry too large",
+                "Archive extraction failed: a.zip!/large.txt.gz: entry too large",
+            ]
+        );
+    }
+
+    #[test]
+    fn shares_limits_with_nested_walks() {
+        // An archive found by the visitor (e.g. in an email) counts against the same
+        // depth and entry limits
+        let inner = zip(&[("a.png", b"a"), ("b.png", b"b")]);
+        let outer = zip(&[("mail.eml", &[b"mail:", inner.as_slice()].concat())]);
+        let limits = ArchiveLimits {
+            max_entries: 2,
+            ..ArchiveLimits::default()
+        };
+
+        let result = walk(&outer, &limits, &mut |walker, path, data| {
+            walker.walk(&data[5..], path, &mut |_, _, _| Ok(()))
+        });
+        assert!(
+            matches!(result, Err(CredentialDetectorError::ArchiveError(e)) if e.contains("too many entries"))
+        );
+
+        let limits = ArchiveLimits {
+            max_depth: 0,
+            ..ArchiveLimits::default()
+        };
+        let skipped = walk(&outer, &limits, &mut |walker, path, data| {
+            walker.walk(&data[5..], path, &mut |_, _, _| panic!("nested too deep"))
+        })
+        .unwrap();
+        assert_eq!(
+            skipped,
+            ["Archive extraction failed: a.zip!/mail.eml: nesting too deep"]
+        );
+    }
+
+    #[test]
+    fn stops_at_limits() {
+        let nested = zip(&[("a.png", b"a"), ("b.png", b"b")]);
+        let outer = zip(&[("nested.zip", &nested), ("c.png", b"c")]);
+        let limits = ArchiveLimits {
+            max_entries: 2,
+            ..ArchiveLimits::default()
+        };
+
+        let result = walk(&outer, &limits, &mut |_, _, _| Ok(()));
+        assert!(
+            matches!(result, Err(CredentialDetectorError::ArchiveError(e)) if e.contains("too many entries"))
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! Text documents with embedded images
+//!
+//! Wiki exports, HTML pages and Markdown files embed screenshots as base64 data URIs
+//! (`<img src="data:image/png;base64,...">`, `![](data:image/png;base64,...)`).
+
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD_NO_PAD, URL_SAFE_NO_PAD};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// File extensions of text documents that may embed images as data URIs
+const DOCUMENT_EXTENSIONS: [&str; 6] = ["html", "htm", "xhtml", "md", "markdown", "svg"];
+
+/// Determine if a file name has the extension of a text document
+pub(crate) fn is_document(name: &str) -> bool {
+    name.rsplit_once('.').is_some_and(|(_, extension)| {
+        DOCUMENT_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+    })
+}
+
+/// Regular expression representing base64 image data URIs, which may be wrapped
+fn data_uri_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"data:(image/[A-Za-z0-9.+\-]+)(?:;[A-Za-z0-9\-]+=[^;,\s]*)*;base64,([A-Za-z0-9+/_\-=\s]+)")
+            .unwrap()
+    })
+}
+
+/// Decode standard or URL-safe base64, with or without padding
+fn decode_base64(encoded: &str) -> Option<Vec<u8>> {
+    let encoded = encoded.trim_end_matches('=');
+    STANDARD_NO_PAD
+        .decode(encoded)
+        .or_else(|_| URL_SAFE_NO_PAD.decode(encoded))
+        .ok()
+}
+
+/// Image decoded from a data URI
+pub(crate) struct DataUriImage {
+    /// Media type declared by the URI (e.g. `image/png`)
+    pub media_type: String,
+    /// Decoded image data
+    pub data: Vec<u8>,
+    /// Byte offset of the URI in the document
+    pub offset: usize,
+    /// Line of the URI in the document, starting at 1
+    pub line: usize,
+}
+
+/// Find and decode the base64 image data URIs of a document
+pub(crate) fn data_uri_images(document: &str) -> Vec<DataUriImage> {
+    data_uri_pattern()
+        .captures_iter(document)
+        .filter_map(|capture| {
+            let start = capture.get(0)?.start();
+            // Wrapped data is joined across lines; when that swallowed trailing text,
+            // only the data up to the first whitespace is decoded
+            let wrapped: String = capture[2].chars().filter(|c| !c.is_whitespace()).collect();
+            let unwrapped = capture[2].split(char::is_whitespace).next()?;
+            let data = decode_base64(&wrapped).or_else(|| decode_base64(unwrapped))?;
+
+            Some(DataUriImage {
+                media_type: capture[1].to_string(),
+                data,
+                offset: start,
+                line: document[..start].matches('\n').count() + 1,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognises_document_extensions() {
+        assert!(is_document("wiki/page.HTML"));
+        assert!(is_document("README.md"));
+        assert!(!is_document("image.png"));
+        assert!(!is_document("markdown"));
+    }
+
+    #[test]
+    fn decodes_html_data_uris() {
+        let document = "<p>Login</p>\n<img alt=\"x\" src=\"data:image/png;base64,aW1hZ2U=\">";
+        let images = data_uri_images(document);
+
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].media_type, "image/png");
+        assert_eq!(images[0].data, b"image");
+        assert_eq!(images[0].line, 2);
+        assert_eq!(images[0].offset, document.find("data:").unwrap());
+    }
+
+    #[test]
+    fn decodes_markdown_data_uris() {
+        let document = "# Setup\n\n![token](data:image/gif;name=shot.gif;base64,c2hvdA)\n";
+        let images = data_uri_images(document);
+
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].media_type, "image/gif");
+        assert_eq!(images[0].data, b"shot");
+        assert_eq!(images[0].line, 3);
+    }
+
+    #[test]
+    fn joins_wrapped_data() {
+        let document = "<img src=\"data:image/png;base64,aW1h\n  Z2U=\">";
+        let images = data_uri_images(document);
+
+        assert_eq!(images[0].data, b"image");
+        assert_eq!(images[0].line, 1);
+    }
+
+    #[test]
+    fn skips_malformed_data_uris() {
+        // Invalid base64, a non-image media type and a URI without the base64 marker
+        let document = "data:image/png;base64,a\ndata:text/plain;base64,aW1hZ2U=\n\
+                        data:image/png,aW1hZ2U=\n![](data:image/png;base64,aW1hZ2U=)";
+        let images = data_uri_images(document);
+
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].line, 4);
+    }
+}
@@ -0,0 +1,172 @@
+//! Email messages
+//!
+//! HTML emails carry screenshots as attachments, as inline parts referenced by `cid:`
+//! URLs, or as data URIs in the HTML body. Attached (forwarded) messages are descended
+//! into.
+
+use crate::error::CredentialDetectorError;
+use mail_parser::{Message, MessageParser, MimeHeaders, PartType};
+
+/// Content of a leaf part of a message
+pub(crate) enum PartContent {
+    /// Attachment or inline binary part
+    Binary(Vec<u8>),
+    /// Text or HTML body, which may embed data URIs
+    Text(String),
+}
+
+/// Leaf part of a message
+pub(crate) struct EmailPart {
+    /// Position of the part among the leaf parts of the message, starting at 0
+    pub index: usize,
+    /// File name or Content-ID of the part, if any
+    pub name: Option<String>,
+    pub content: PartContent,
+}
+
+/// Parsed email message
+pub(crate) struct Email {
+    pub subject: Option<String>,
+    /// Address of the sender
+    pub from: Option<String>,
+    pub parts: Vec<EmailPart>,
+}
+
+/// Collect the leaf parts of a message, including those of attached messages
+fn collect_parts(message: &Message, parts: &mut Vec<EmailPart>) {
+    for part in &message.parts {
+        let content = match &part.body {
+            PartType::Text(text) | PartType::Html(text) => PartContent::Text(text.to_string()),
+            PartType::Binary(data) | PartType::InlineBinary(data) => {
+                PartContent::Binary(data.to_vec())
+            }
+            PartType::Message(nested) => {
+                collect_parts(nested, parts);
+                continue;
+            }
+            PartType::Multipart(_) => continue,
+        };
+
+        let name = part
+            .attachment_name()
+            .or_else(|| part.content_id())
+            .map(str::to_string);
+        parts.push(EmailPart {
+            index: parts.len(),
+            name,
+            content,
+        });
+    }
+}
+
+/// Parse an RFC 5322 message (`.eml`)
+pub(crate) fn read_email(eml_data: &[u8]) -> Result<Email, CredentialDetectorError> {
+    let Some(message) = MessageParser::default().parse(eml_data) else {
+        return Err(CredentialDetectorError::EmailParseError(
+            "Not an email message".to_string(),
+        ));
+    };
+
+    let mut parts = Vec::new();
+    collect_parts(&message, &mut parts);
+
+    Ok(Email {
+        subject: message.subject().map(str::to_string),
+        from: message
+            .from()
+            .and_then(|from| from.first())
+            .and_then(|from| from.address())
+            .map(str::to_string),
+        parts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &str = "From: Alice <alice@example.com>\r\n\
+        Subject: Dashboard\r\n\
+        MIME-Version: 1.0\r\n\
+        Content-Type: multipart/mixed; boundary=\"outer\"\r\n\
+        \r\n\
+        --outer\r\n\
+        Content-Type: multipart/related; boundary=\"inner\"\r\n\
+        \r\n\
+        --inner\r\n\
+        Content-Type: text/html\r\n\
+        \r\n\
+        <img src=\"cid:shot@example.com\">\r\n\
+        --inner\r\n\
+        Content-Type: image/png\r\n\
+        Content-ID: <shot@example.com>\r\n\
+        Content-Disposition: inline\r\n\
+        Content-Transfer-Encoding: base64\r\n\
+        \r\n\
+        aW5saW5l\r\n\
+        --inner--\r\n\
+        --outer\r\n\
+        Content-Type: application/octet-stream\r\n\
+        Content-Disposition: attachment; filename=\"keys.png\"\r\n\
+        Content-Transfer-Encoding: base64\r\n\
+        \r\n\
+        YXR0YWNoZWQ=\r\n\
+        --outer\r\n\
+        Content-Type: message/rfc822\r\n\
+        \r\n\
+        From: bob@example.com\r\n\
+        Subject: Fwd\r\n\
+        Content-Type: image/gif; name=\"forwarded.gif\"\r\n\
+        Content-Transfer-Encoding: base64\r\n\
+        \r\n\
+        Zm9yd2FyZGVk\r\n\
+        --outer--\r\n";
+
+    fn binary(part: &EmailPart) -> &[u8] {
+        match &part.content {
+            PartContent::Binary(data) => data,
+            PartContent::Text(_) => panic!("text part {}", part.index),
+        }
+    }
+
+    #[test]
+    fn reads_headers() {
+        let email = read_email(MESSAGE.as_bytes()).unwrap();
+
+        assert_eq!(email.subject.as_deref(), Some("Dashboard"));
+        assert_eq!(email.from.as_deref(), Some("alice@example.com"));
+    }
+
+    #[test]
+    fn extracts_attachments_and_inline_parts() {
+        let email = read_email(MESSAGE.as_bytes()).unwrap();
+
+        let indices: Vec<_> = email.parts.iter().map(|part| part.index).collect();
+        assert_eq!(indices, [0, 1, 2, 3]);
+
+        // The HTML body references the inline part by its Content-ID
+        assert!(
+            matches!(&email.parts[0].content, PartContent::Text(html) if html.contains("cid:shot@example.com"))
+        );
+        assert_eq!(email.parts[1].name.as_deref(), Some("shot@example.com"));
+        assert_eq!(binary(&email.parts[1]), b"inline");
+        assert_eq!(email.parts[2].name.as_deref(), Some("keys.png"));
+        assert_eq!(binary(&email.parts[2]), b"attached");
+    }
+
+    #[test]
+    fn descends_into_attached_messages() {
+        let email = read_email(MESSAGE.as_bytes()).unwrap();
+
+        assert_eq!(email.parts[3].name.as_deref(), Some("forwarded.gif"));
+        assert_eq!(binary(&email.parts[3]), b"forwarded");
+    }
+
+    #[test]
+    fn rejects_empty_input() {
+        assert!(matches!(
+            read_email(b""),
+            Err(CredentialDetectorError::EmailParseError(_))
+        ));
+    }
+}
@@ -0,0 +1,694 @@
+//! Multi-frame images
+//!
+//! Screen recordings exported as animated GIF or APNG and scanned multi-page TIFFs
+//! hide text in frames after the first, which `image::load_from_memory` never decodes.
+
+use crate::error::CredentialDetectorError;
+use crate::sources::{cmyk_to_rgb, unpack_samples};
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::imageops::FilterType;
+use image::{
+    AnimationDecoder, DynamicImage, GrayAlphaImage, GrayImage, ImageFormat, RgbImage, RgbaImage,
+};
+use std::io::Cursor;
+use std::time::Duration;
+use tiff::ColorType;
+use tiff::decoder::{Decoder, DecodingResult};
+
+/// Size of the thumbnails compared to find unchanged frames
+const THUMBNAIL_SIZE: u32 = 256;
+
+/// Difference in gray level for a thumbnail pixel to count as changed
+const PIXEL_CHANGE_THRESHOLD: u8 = 32;
+
+/// Frame sampling for multi-frame images and video
+///
+/// Consecutive frames of a recording are mostly identical; frames whose thumbnail
+/// barely differs from the last scanned frame are skipped.
+///
+/// The default scans every changed frame, so a secret shown only briefly is not missed.
+/// Use [`FrameSampling::with_frames_per_second`] to bound the work on long recordings,
+/// or [`FrameSampling::every_frame`] to scan unchanged frames too.
+#[derive(Debug, Clone)]
+pub struct FrameSampling {
+    /// Scan every `step`-th frame (1 scans every frame)
+    pub step: usize,
+    /// Scan at most one frame per interval of presentation time (timed sources only)
+    pub interval: Option<Duration>,
+    /// Maximum number of frames to scan
+    pub max_frames: Option<usize>,
+    /// Fraction of thumbnail pixels that must change since the last scanned frame for
+    /// a frame to be scanned (0.0 scans every sampled frame)
+    pub min_change: f32,
+}
+
+impl FrameSampling {
+    /// Create a new frame sampling scanning every `step`-th frame
+    pub fn new(step: usize) -> Self {
+        Self {
+            step: step.max(1),
+            ..Self::default()
+        }
+    }
+
+    /// Scan every frame, including unchanged ones
+    pub fn every_frame() -> Self {
+        Self {
+            step: 1,
+            interval: None,
+            max_frames: None,
+            min_change: 0.0,
+        }
+    }
+
+    /// Set the sampling rate of timed sources (animations, video) in frames per second
+    ///
+    /// Zero, negative, infinite and NaN rates disable time-based sampling.
+    pub fn with_frames_per_second(mut self, frames_per_second: f32) -> Self {
+        self.interval = if frames_per_second.is_finite() && frames_per_second > 0.0 {
+            // Rates too low for a `Duration` sample only the first frame
+            Some(
+                Duration::try_from_secs_f64(1.0 / frames_per_second as f64)
+                    .unwrap_or(Duration::MAX),
+            )
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Set the maximum number of frames to scan
+    pub fn with_max_frames(mut self, max_frames: usize) -> Self {
+        self.max_frames = Some(max_frames);
+        self
+    }
+
+    /// Set the fraction of thumbnail pixels that must change for a frame to be scanned
+    pub fn with_min_change(mut self, min_change: f32) -> Self {
+        self.min_change = min_change;
+        self
+    }
+}
+
+impl Default for FrameSampling {
+    fn default() -> Self {
+        Self {
+            step: 1,
+            interval: None,
+            max_frames: None,
+            min_change: 0.0005,
+        }
+    }
+}
+
+/// Decoded frame of a multi-frame image or a video
+pub(crate) struct Frame {
+    /// Position of the frame in the source, starting at 0
+    pub index: usize,
+    /// Presentation time of the frame, when the source is timed
+    pub timestamp: Option<Duration>,
+    pub image: DynamicImage,
+}
+
+/// Grayscale thumbnail used to compare frames
+fn thumbnail(image: &DynamicImage) -> GrayImage {
+    image
+        .resize(THUMBNAIL_SIZE, THUMBNAIL_SIZE, FilterType::Triangle)
+        .to_luma8()
+}
+
+/// Fraction of the pixels that differ between two thumbnails
+fn changed_fraction(a: &GrayImage, b: &GrayImage) -> f32 {
+    if a.dimensions() != b.dimensions() {
+        return 1.0;
+    }
+
+    let changed = a
+        .pixels()
+        .zip(b.pixels())
+        .filter(|(a, b)| a[0].abs_diff(b[0]) > PIXEL_CHANGE_THRESHOLD)
+        .count();
+    changed as f32 / (a.width() * a.height()) as f32
+}
+
+/// Selects the frames to scan according to a [`FrameSampling`]
+pub(crate) struct FrameSampler<'a> {
+    sampling: &'a FrameSampling,
+    scanned: usize,
+    /// Presentation time of the last sampled frame
+    last_sampled: Option<Duration>,
+    /// Thumbnail of the last scanned frame
+    last_scanned: Option<GrayImage>,
+    /// Last sampled frame that was skipped as unchanged
+    pending: Option<(Frame, GrayImage)>,
+}
+
+impl<'a> FrameSampler<'a> {
+    pub fn new(sampling: &'a FrameSampling) -> Self {
+        Self {
+            sampling,
+            scanned: 0,
+            last_sampled: None,
+            last_scanned: None,
+            pending: None,
+        }
+    }
+
+    /// Determine if the maximum number of frames has been scanned
+    pub fn is_done(&self) -> bool {
+        self.sampling
+            .max_frames
+            .is_some_and(|max_frames| self.scanned >= max_frames)
+    }
+
+    /// Offer a frame, returning it if it should be scanned
+    pub fn push(&mut self, frame: Frame) -> Option<Frame> {
+        if self.is_done() || !frame.index.is_multiple_of(self.sampling.step.max(1)) {
+            return None;
+        }
+        if let (Some(interval), Some(timestamp)) = (self.sampling.interval, frame.timestamp) {
+            if self
+                .last_sampled
+                .is_some_and(|last_sampled| timestamp < last_sampled.saturating_add(interval))
+            {
+                return None;
+            }
+            self.last_sampled = Some(timestamp);
+        }
+
+        let thumbnail = thumbnail(&frame.image);
+        if let Some(last_scanned) = &self.last_scanned
+            && changed_fraction(last_scanned, &thumbnail) < self.sampling.min_change
+        {
+            self.pending = Some((frame, thumbnail));
+            return None;
+        }
+
+        self.pending = None;
+        self.last_scanned = Some(thumbnail);
+        self.scanned += 1;
+        Some(frame)
+    }
+
+    /// Return the last skipped frame if it differs at all from the last scanned one
+    ///
+    /// Changes below the threshold accumulate (e.g. a token typed character by
+    /// character), so the final state is scanned even when no single step was large.
+    pub fn finish(&mut self) -> Option<Frame> {
+        let (frame, thumbnail) = self.pending.take()?;
+        let last_scanned = self.last_scanned.as_ref()?;
+        if self.is_done() || changed_fraction(last_scanned, &thumbnail) == 0.0 {
+            return None;
+        }
+
+        self.scanned += 1;
+        Some(frame)
+    }
+}
+
+pub(crate) type Frames<'a> = Box<dyn Iterator<Item = Result<Frame, CredentialDetectorError>> + 'a>;
+
+/// Frames of an animated GIF or APNG
+fn animation_frames<'a>(decoder: impl AnimationDecoder<'a>) -> Frames<'a> {
+    let mut elapsed = Duration::ZERO;
+    let mut failed = false;
+    Box::new(
+        decoder
+            .into_frames()
+            .enumerate()
+            // The decoders keep reporting the same error, so stop after the first one
+            .map_while(move |(index, frame)| {
+                if failed {
+                    return None;
+                }
+                let frame = match frame {
+                    Ok(frame) => frame,
+                    Err(e) => {
+                        failed = true;
+                        return Some(Err(CredentialDetectorError::ImageLoadError(e.to_string())));
+                    }
+                };
+                let timestamp = elapsed;
+                elapsed += Duration::from(frame.delay());
+                Some(Ok(Frame {
+                    index,
+                    timestamp: Some(timestamp),
+                    image: DynamicImage::ImageRgba8(frame.into_buffer()),
+                }))
+            }),
+    )
+}
+
+/// Pages of a TIFF file
+struct TiffPages<'a> {
+    decoder: Decoder<Cursor<&'a [u8]>>,
+    index: usize,
+    done: bool,
+}
+
+impl TiffPages<'_> {
+    /// Decode the current page
+    fn read_page(&mut self) -> Result<DynamicImage, tiff::TiffError> {
+        let (width, height) = self.decoder.dimensions()?;
+        let color_type = self.decoder.colortype()?;
+
+        let (components, bits) = match color_type {
+            ColorType::Gray(bits) => (1, bits),
+            ColorType::GrayA(bits) => (2, bits),
+            ColorType::RGB(bits) => (3, bits),
+            ColorType::RGBA(bits) | ColorType::CMYK(bits) => (4, bits),
+            _ => return Err(unsupported(color_type)),
+        };
+        // Other depths below 8 bits would split samples across bytes
+        if bits < 8 && !matches!(bits, 1 | 2 | 4) {
+            return Err(unsupported(color_type));
+        }
+
+        let mut samples = match self.decoder.read_image()? {
+            DecodingResult::U8(samples) => samples,
+            DecodingResult::U16(samples) => {
+                // Scale by the actual depth, so that 12-bit data keeps its brightness
+                let max = (1u32 << bits.clamp(9, 16)) - 1;
+                samples
+                    .iter()
+                    .map(|&s| ((s as u32).min(max) * 255 / max) as u8)
+                    .collect()
+            }
+            _ => return Err(unsupported(color_type)),
+        };
+
+        if bits < 8 {
+            let row_samples = width as usize * components;
+            samples = unpack_samples(&samples, bits as usize, row_samples, height as usize);
+            let max = (1u16 << bits) - 1;
+            for sample in &mut samples {
+                *sample = (*sample as u16 * 255 / max) as u8;
+            }
+        }
+
+        let image = match color_type {
+            ColorType::Gray(_) => {
+                GrayImage::from_raw(width, height, samples).map(DynamicImage::ImageLuma8)
+            }
+            ColorType::GrayA(_) => {
+                GrayAlphaImage::from_raw(width, height, samples).map(DynamicImage::ImageLumaA8)
+            }
+            ColorType::RGB(_) => {
+                RgbImage::from_raw(width, height, samples).map(DynamicImage::ImageRgb8)
+            }
+            ColorType::RGBA(_) => {
+                RgbaImage::from_raw(width, height, samples).map(DynamicImage::ImageRgba8)
+            }
+            _ => RgbImage::from_raw(width, height, cmyk_to_rgb(&samples))
+                .map(DynamicImage::ImageRgb8),
+        };
+        image.ok_or_else(|| unsupported(color_type))
+    }
+}
+
+/// Error for TIFF pages whose layout cannot be converted
+fn unsupported(color_type: ColorType) -> tiff::TiffError {
+    tiff::TiffError::UnsupportedError(tiff::TiffUnsupportedError::UnsupportedColorType(color_type))
+}
+
+impl Iterator for TiffPages<'_> {
+    type Item = Result<Frame, CredentialDetectorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if self.index > 0 {
+            if !self.decoder.more_images() {
+                self.done = true;
+                return None;
+            }
+            if let Err(e) = self.decoder.next_image() {
+                self.done = true;
+                return Some(Err(CredentialDetectorError::ImageLoadError(e.to_string())));
+            }
+        }
+
+        let index = self.index;
+        self.index += 1;
+        Some(
+            self.read_page()
+                .map(|image| Frame {
+                    index,
+                    timestamp: None,
+                    image,
+                })
+                .map_err(|e| CredentialDetectorError::ImageLoadError(e.to_string())),
+        )
+    }
+}
+
+/// Count the images of a GIF file, up to `limit`, by walking its blocks without decoding
+fn gif_frame_count(data: &[u8], limit: usize) -> usize {
+    // Skip the data sub-blocks starting at `position`
+    let skip_sub_blocks = |mut position: usize| -> Option<usize> {
+        loop {
+            let size = *data.get(position)? as usize;
+            position += 1 + size;
+            if size == 0 {
+                return Some(position);
+            }
+        }
+    };
+    // Size of the color table announced by a packed field
+    let color_table_size = |packed: u8| {
+        if packed & 0x80 != 0 {
+            3 << ((packed & 0x07) + 1)
+        } else {
+            0
+        }
+    };
+
+    let mut count = 0;
+    // Header and logical screen descriptor
+    let Some(&packed) = data.get(10) else {
+        return 0;
+    };
+    let mut position = 13 + color_table_size(packed);
+
+    while count < limit {
+        match data.get(position) {
+            // Extension: introducer, label and sub-blocks
+            Some(0x21) => match skip_sub_blocks(position + 2) {
+                Some(next) => position = next,
+                None => break,
+            },
+            // Image descriptor, optional local color table, LZW code size and sub-blocks
+            Some(0x2C) => {
+                count += 1;
+                let Some(&packed) = data.get(position + 9) else {
+                    break;
+                };
+                match skip_sub_blocks(position + 10 + color_table_size(packed) + 1) {
+                    Some(next) => position = next,
+                    None => break,
+                }
+            }
+            // Trailer, or anything unexpected
+            _ => break,
+        }
+    }
+
+    count
+}
+
+/// Read the number of frames an APNG file announces (0 for still PNG files)
+fn apng_frame_count(data: &[u8]) -> u32 {
+    // Chunks after the signature: length, type, data and CRC
+    let mut position = 8;
+    while let Some(header) = data.get(position..position + 8) {
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        match &header[4..8] {
+            b"acTL" => {
+                return data.get(position + 8..position + 12).map_or(0, |frames| {
+                    u32::from_be_bytes([frames[0], frames[1], frames[2], frames[3]])
+                });
+            }
+            // The animation control chunk comes before the image data
+            b"IDAT" => return 0,
+            _ => position = position.saturating_add(12).saturating_add(length),
+        }
+    }
+    0
+}
+
+/// Read the frames of an animated GIF or APNG, or the pages of a multi-page TIFF
+///
+/// Returns `None` for other formats and for single-frame images, which are handled as
+/// plain images. The number of frames is read from the file structure, so still
+/// images are not decoded here.
+pub(crate) fn read_frames(image_data: &[u8]) -> Option<Frames<'_>> {
+    let reader = Cursor::new(image_data);
+    match image::guess_format(image_data).ok()? {
+        ImageFormat::Gif if gif_frame_count(image_data, 2) > 1 => {
+            Some(animation_frames(GifDecoder::new(reader).ok()?))
+        }
+        ImageFormat::Png if apng_frame_count(image_data) > 1 => {
+            Some(animation_frames(PngDecoder::new(reader).ok()?.apng()))
+        }
+        ImageFormat::Tiff => {
+            let decoder = Decoder::new(reader).ok()?;
+            if !decoder.more_images() {
+                return None;
+            }
+            Some(Box::new(TiffPages {
+                decoder,
+                index: 0,
+                done: false,
+            }))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::codecs::gif::GifEncoder;
+    use image::{Delay, Rgba};
+
+    /// Solid frame of the given gray level
+    fn solid(level: u8) -> RgbaImage {
+        RgbaImage::from_pixel(32, 32, Rgba([level, level, level, 255]))
+    }
+
+    /// Encode an animated GIF with one frame per gray level, 100 ms apart
+    fn gif(levels: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut encoder = GifEncoder::new(&mut data);
+        encoder
+            .encode_frames(levels.iter().map(|&level| {
+                image::Frame::from_parts(solid(level), 0, 0, Delay::from_numer_denom_ms(100, 1))
+            }))
+            .unwrap();
+        drop(encoder);
+        data
+    }
+
+    /// Single-row grayscale TIFF with `bits` bits per sample
+    fn gray_tiff(bits: u16, width: u32, data: &[u8]) -> Vec<u8> {
+        let entries: [(u16, u16, u32); 9] = [
+            (256, 4, width),
+            (257, 4, 1),
+            (258, 3, bits as u32),
+            (259, 3, 1),
+            (262, 3, 1),
+            (273, 4, 8 + 2 + 9 * 12 + 4),
+            (277, 3, 1),
+            (278, 4, 1),
+            (279, 4, data.len() as u32),
+        ];
+        let mut tiff = b"II*\0".to_vec();
+        tiff.extend_from_slice(&8u32.to_le_bytes());
+        tiff.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        for (tag, kind, value) in entries {
+            tiff.extend_from_slice(&tag.to_le_bytes());
+            tiff.extend_from_slice(&kind.to_le_bytes());
+            tiff.extend_from_slice(&1u32.to_le_bytes());
+            tiff.extend_from_slice(&value.to_le_bytes());
+        }
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+        tiff.extend_from_slice(data);
+        tiff
+    }
+
+    /// Decode the first page of a TIFF file to gray levels
+    fn tiff_levels(data: &[u8]) -> Result<Vec<u8>, CredentialDetectorError> {
+        let mut pages = TiffPages {
+            decoder: Decoder::new(Cursor::new(data)).unwrap(),
+            index: 0,
+            done: false,
+        };
+        pages
+            .next()
+            .unwrap()
+            .map(|frame| frame.image.to_luma8().into_raw())
+    }
+
+    fn frame(index: usize, timestamp_ms: Option<u64>, level: u8) -> Frame {
+        Frame {
+            index,
+            timestamp: timestamp_ms.map(Duration::from_millis),
+            image: DynamicImage::ImageRgba8(solid(level)),
+        }
+    }
+
+    #[test]
+    fn counts_gif_frames_without_decoding() {
+        assert_eq!(gif_frame_count(&gif(&[0]), 10), 1);
+        assert_eq!(gif_frame_count(&gif(&[0, 255, 0]), 10), 3);
+        assert_eq!(gif_frame_count(&gif(&[0, 255, 0]), 2), 2);
+        assert_eq!(gif_frame_count(b"GIF89a", 2), 0);
+    }
+
+    #[test]
+    fn counts_apng_frames() {
+        let chunk = |kind: &[u8], data: &[u8]| {
+            let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+            chunk.extend_from_slice(kind);
+            chunk.extend_from_slice(data);
+            chunk.extend_from_slice(&[0; 4]);
+            chunk
+        };
+        let png = |chunks: &[Vec<u8>]| {
+            let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+            png.extend(chunks.concat());
+            png
+        };
+
+        let header = chunk(b"IHDR", &[0; 13]);
+        let animation = chunk(b"acTL", &[0, 0, 0, 3, 0, 0, 0, 0]);
+        let data = chunk(b"IDAT", &[]);
+
+        assert_eq!(
+            apng_frame_count(&png(&[header.clone(), animation, data.clone()])),
+            3
+        );
+        assert_eq!(apng_frame_count(&png(&[header.clone(), data])), 0);
+        assert_eq!(apng_frame_count(&png(&[header])), 0);
+    }
+
+    #[test]
+    fn reads_only_animated_gifs() {
+        assert!(read_frames(&gif(&[0])).is_none());
+
+        let frames: Vec<Frame> = read_frames(&gif(&[0, 255, 0]))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[2].index, 2);
+        assert_eq!(frames[2].timestamp, Some(Duration::from_millis(200)));
+    }
+
+    #[test]
+    fn reports_truncated_frames() {
+        let data = gif(&[0, 255, 0]);
+        // Cut the file inside the image data of the last frame
+        let frames: Vec<_> = read_frames(&data[..data.len() - 8]).unwrap().collect();
+
+        assert!(frames[0].is_ok());
+        // Decoding ends with the first error
+        assert!(frames.last().unwrap().is_err());
+        assert_eq!(frames.iter().filter(|frame| frame.is_err()).count(), 1);
+    }
+
+    #[test]
+    fn samples_by_step_and_interval() {
+        let sampling = FrameSampling::every_frame().with_frames_per_second(5.0);
+        let mut sampler = FrameSampler::new(&sampling);
+
+        // Frames 100 ms apart at 5 frames per second
+        let scanned: Vec<usize> = (0..6)
+            .filter_map(|i| sampler.push(frame(i, Some(i as u64 * 100), i as u8 * 40)))
+            .map(|frame| frame.index)
+            .collect();
+        assert_eq!(scanned, [0, 2, 4]);
+
+        let sampling = FrameSampling::new(3).with_max_frames(2);
+        let mut sampler = FrameSampler::new(&sampling);
+        let scanned: Vec<usize> = (0..10)
+            .filter_map(|i| sampler.push(frame(i, None, i as u8 * 20)))
+            .map(|frame| frame.index)
+            .collect();
+        assert_eq!(scanned, [0, 3]);
+        assert!(sampler.is_done());
+    }
+
+    #[test]
+    fn validates_frame_rates() {
+        let interval = |fps: f32| {
+            FrameSampling::default()
+                .with_frames_per_second(fps)
+                .interval
+        };
+
+        assert_eq!(interval(5.0), Some(Duration::from_millis(200)));
+        assert_eq!(interval(0.0), None);
+        assert_eq!(interval(-1.0), None);
+        assert_eq!(interval(f32::NAN), None);
+        assert_eq!(interval(f32::INFINITY), None);
+        assert_eq!(interval(f32::MIN_POSITIVE), Some(Duration::MAX));
+
+        // A huge interval only samples the first frame
+        let sampling = FrameSampling::every_frame().with_frames_per_second(f32::MIN_POSITIVE);
+        let mut sampler = FrameSampler::new(&sampling);
+        assert!(sampler.push(frame(0, Some(0), 0)).is_some());
+        assert!(sampler.push(frame(1, Some(1000), 255)).is_none());
+    }
+
+    #[test]
+    fn skips_unchanged_frames_but_scans_the_final_state() {
+        let sampling = FrameSampling::new(1).with_min_change(0.5);
+        let mut sampler = FrameSampler::new(&sampling);
+
+        assert!(sampler.push(frame(0, None, 0)).is_some());
+        // A change on a quarter of the pixels is below the threshold
+        let mut image = solid(0);
+        for y in 0..16 {
+            for x in 0..16 {
+                image.put_pixel(x, y, Rgba([255, 255, 255, 255]));
+            }
+        }
+        let changed = Frame {
+            index: 1,
+            timestamp: None,
+            image: DynamicImage::ImageRgba8(image),
+        };
+        assert!(sampler.push(changed).is_none());
+        assert_eq!(sampler.finish().map(|frame| frame.index), Some(1));
+
+        // Identical frames are never scanned again
+        let mut sampler = FrameSampler::new(&sampling);
+        assert!(sampler.push(frame(0, None, 0)).is_some());
+        assert!(sampler.push(frame(1, None, 0)).is_none());
+        assert!(sampler.finish().is_none());
+    }
+
+    #[test]
+    fn scans_every_changed_frame_by_default() {
+        let sampling = FrameSampling::default();
+        let mut sampler = FrameSampler::new(&sampling);
+
+        // A frame shown for 40 ms is still scanned
+        let scanned: Vec<usize> = (0..4)
+            .filter_map(|i| sampler.push(frame(i, Some(i as u64 * 40), i as u8 * 80)))
+            .map(|frame| frame.index)
+            .collect();
+        assert_eq!(scanned, [0, 1, 2, 3]);
+        assert!(sampler.push(frame(4, Some(160), 240)).is_none());
+    }
+
+    #[test]
+    fn scales_tiff_samples_by_bit_depth() {
+        let mut wide = Vec::new();
+        for sample in [0xffffu16, 0x8000, 0] {
+            wide.extend_from_slice(&sample.to_le_bytes());
+        }
+        assert_eq!(
+            tiff_levels(&gray_tiff(16, 3, &wide)).unwrap(),
+            [255, 127, 0]
+        );
+        // The decoder reads one byte per sample, so packed rows are padded
+        assert_eq!(
+            tiff_levels(&gray_tiff(4, 3, &[0xf8, 0, 0])).unwrap(),
+            [255, 136, 0]
+        );
+        assert_eq!(
+            tiff_levels(&gray_tiff(1, 3, &[0xa0, 0, 0])).unwrap(),
+            [255, 0, 255]
+        );
+    }
+
+    #[test]
+    fn rejects_tiff_samples_split_across_bytes() {
+        assert!(tiff_levels(&gray_tiff(3, 3, &[0xff, 0xff, 0xff])).is_err());
+        assert!(tiff_levels(&gray_tiff(6, 3, &[0xff, 0xff, 0xff])).is_err());
+    }
+}
@@ -0,0 +1,342 @@
+//! Git history
+//!
+//! Screenshots committed to a repository and later deleted remain in its history.
+//! Every commit reachable from any reference is walked and the image blobs of its
+//! tree are listed, each unique blob once, attributed to the earliest commit found to
+//! contain it.
+
+use crate::error::CredentialDetectorError;
+use crate::sources::document;
+use gix::ObjectId;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Blobs larger than this are not read
+const MAX_BLOB_SIZE: u64 = 64 << 20;
+
+/// Image file extensions, matched case-insensitively
+const IMAGE_EXTENSIONS: [&str; 9] = [
+    "png", "jpg", "jpeg", "gif", "webp", "bmp", "tif", "tiff", "ico",
+];
+
+/// Determine if a file in the history should be scanned, judging by its extension
+///
+/// Reading every blob to sniff its format would decompress the whole history.
+fn is_scannable_path(path: &str) -> bool {
+    let Some((_, extension)) = path.rsplit_once('.') else {
+        return false;
+    };
+    let extension = extension.to_ascii_lowercase();
+    let extension = extension.as_str();
+
+    #[cfg(feature = "pdf")]
+    if extension == "pdf" {
+        return true;
+    }
+    #[cfg(feature = "video")]
+    if matches!(extension, "mp4" | "m4v" | "mov") {
+        return true;
+    }
+    #[cfg(feature = "archive")]
+    if matches!(
+        extension,
+        "zip" | "docx" | "pptx" | "xlsx" | "odt" | "odp" | "ods" | "epub" | "tar" | "gz" | "tgz"
+    ) {
+        return true;
+    }
+    #[cfg(feature = "email")]
+    if extension == "eml" {
+        return true;
+    }
+
+    IMAGE_EXTENSIONS.contains(&extension) || document::is_document(path)
+}
+
+/// Git error from a gix error
+fn git_error(error: impl std::fmt::Display) -> CredentialDetectorError {
+    CredentialDetectorError::GitError(error.to_string())
+}
+
+/// Blob found in the history, with the commit that first contained it
+pub(crate) struct GitBlob {
+    pub id: ObjectId,
+    /// Path of the blob in the commit's tree
+    pub path: String,
+    /// Id of the commit
+    pub commit: String,
+    /// Author of the commit (`name <email>`)
+    pub author: String,
+}
+
+/// Local git repository
+pub(crate) struct GitHistory {
+    repository: gix::Repository,
+}
+
+impl GitHistory {
+    /// Open the repository at (or containing) a path
+    pub fn open(path: &Path) -> Result<Self, CredentialDetectorError> {
+        let repository = gix::discover(path).map_err(git_error)?;
+        Ok(Self { repository })
+    }
+
+    /// List the scannable blobs of every commit, each unique blob once
+    pub fn blobs(&self) -> Result<Vec<GitBlob>, CredentialDetectorError> {
+        let repository = &self.repository;
+
+        // Start from every branch, tag and remote-tracking reference, and HEAD
+        let mut tips = Vec::new();
+        let references = repository.references().map_err(git_error)?;
+        for reference in references.all().map_err(git_error)? {
+            // References to trees or blobs are ignored
+            if let Ok(mut reference) = reference
+                && let Ok(commit) = reference.peel_to_commit()
+            {
+                tips.push(commit.id);
+            }
+        }
+        if let Ok(commit) = repository.head_commit() {
+            tips.push(commit.id);
+        }
+        if tips.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut walked = Vec::new();
+        let mut parents = HashMap::new();
+        for info in repository.rev_walk(tips).all().map_err(git_error)? {
+            let info = info.map_err(git_error)?;
+            walked.push(info.id);
+            parents.insert(info.id, info.parent_ids.to_vec());
+        }
+
+        // Parents before children, so that each blob is attributed to the commit that
+        // added it even when several branches contain it
+        let mut commits = Vec::with_capacity(walked.len());
+        let mut visited = HashSet::new();
+        for tip in walked {
+            let mut stack = vec![(tip, false)];
+            while let Some((id, expanded)) = stack.pop() {
+                if expanded {
+                    commits.push(id);
+                    continue;
+                }
+                if !visited.insert(id) {
+                    continue;
+                }
+                stack.push((id, true));
+                for parent in parents.get(&id).into_iter().flatten() {
+                    if !visited.contains(parent) {
+                        stack.push((*parent, false));
+                    }
+                }
+            }
+        }
+
+        let mut seen_trees = HashSet::new();
+        let mut seen_blobs = HashSet::new();
+        let mut blobs = Vec::new();
+        for id in commits {
+            let commit = repository.find_commit(id).map_err(git_error)?;
+            let author = match commit.author() {
+                Ok(author) => format!("{} <{}>", author.name, author.email),
+                Err(_) => String::new(),
+            };
+            let tree_id = commit.tree_id().map_err(git_error)?.detach();
+
+            let mut new_blobs = Vec::new();
+            self.walk_tree(
+                tree_id,
+                "",
+                &mut seen_trees,
+                &mut seen_blobs,
+                &mut new_blobs,
+            )?;
+            for (blob_id, path) in new_blobs {
+                blobs.push(GitBlob {
+                    id: blob_id,
+                    path,
+                    commit: id.to_string(),
+                    author: author.clone(),
+                });
+            }
+        }
+
+        Ok(blobs)
+    }
+
+    /// Collect the unseen scannable blobs of a tree, skipping subtrees already walked
+    fn walk_tree(
+        &self,
+        tree_id: ObjectId,
+        prefix: &str,
+        seen_trees: &mut HashSet<ObjectId>,
+        seen_blobs: &mut HashSet<ObjectId>,
+        blobs: &mut Vec<(ObjectId, String)>,
+    ) -> Result<(), CredentialDetectorError> {
+        if !seen_trees.insert(tree_id) {
+            return Ok(());
+        }
+
+        let tree = self.repository.find_tree(tree_id).map_err(git_error)?;
+        for entry in tree.iter() {
+            let entry = entry.map_err(git_error)?;
+            let path = format!("{}{}", prefix, entry.filename());
+            let mode = entry.mode();
+
+            if mode.is_tree() {
+                let prefix = format!("{}/", path);
+                self.walk_tree(entry.object_id(), &prefix, seen_trees, seen_blobs, blobs)?;
+            } else if mode.is_blob()
+                && is_scannable_path(&path)
+                && seen_blobs.insert(entry.object_id())
+            {
+                blobs.push((entry.object_id(), path));
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the contents of a blob, failing if it is too large
+    pub fn read(&self, blob: &GitBlob) -> Result<Vec<u8>, CredentialDetectorError> {
+        let blob_error =
+            |error: &dyn std::fmt::Display| git_error(format!("{}: {}", blob.path, error));
+
+        let header = self
+            .repository
+            .find_header(blob.id)
+            .map_err(|e| blob_error(&e))?;
+        if header.size() > MAX_BLOB_SIZE {
+            return Err(blob_error(&"blob too large"));
+        }
+
+        let object = self
+            .repository
+            .find_object(blob.id)
+            .map_err(|e| blob_error(&e))?;
+        Ok(object.detach().data)
+    }
+}
+
+/// Repository in a temporary directory, removed when dropped
+#[cfg(test)]
+pub(crate) struct TestRepository {
+    pub directory: std::path::PathBuf,
+    pub repository: gix::Repository,
+}
+
+#[cfg(test)]
+impl TestRepository {
+    pub fn new(name: &str) -> Self {
+        let directory =
+            std::env::temp_dir().join(format!("ocd-git-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        let repository = gix::init(&directory).unwrap();
+        Self {
+            directory,
+            repository,
+        }
+    }
+
+    /// Commit a tree of the given files on top of HEAD, returning the commit id
+    pub fn commit(&self, files: &[(&str, &[u8])]) -> ObjectId {
+        use gix::objs::tree::{Entry, EntryKind};
+
+        let mut entries: Vec<Entry> = files
+            .iter()
+            .map(|(name, data)| Entry {
+                mode: EntryKind::Blob.into(),
+                filename: (*name).into(),
+                oid: self.repository.write_blob(data).unwrap().detach(),
+            })
+            .collect();
+        entries.sort();
+        let tree = self
+            .repository
+            .write_object(gix::objs::Tree { entries })
+            .unwrap();
+
+        let author = gix::actor::SignatureRef {
+            name: "Alice".into(),
+            email: "alice@example.com".into(),
+            time: "0 +0000",
+        };
+        let parents: Vec<ObjectId> = self
+            .repository
+            .head_id()
+            .map(|id| id.detach())
+            .into_iter()
+            .collect();
+        self.repository
+            .commit_as(author, author, "HEAD", "Commit", tree, parents)
+            .unwrap()
+            .detach()
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestRepository {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.directory);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_each_blob_once() {
+        let test = TestRepository::new("dedup");
+        let first = test.commit(&[("a.png", b"same"), ("notes.txt", b"text")]);
+        // The same contents under another name, and in a later commit
+        test.commit(&[("a.png", b"same"), ("b.png", b"same"), ("c.png", b"other")]);
+
+        let history = GitHistory::open(&test.directory).unwrap();
+        let blobs = history.blobs().unwrap();
+
+        let paths: Vec<_> = blobs.iter().map(|blob| blob.path.as_str()).collect();
+        assert_eq!(paths, ["a.png", "c.png"]);
+        assert_eq!(blobs[0].commit, first.to_string());
+        assert_eq!(blobs[0].author, "Alice <alice@example.com>");
+        assert_eq!(history.read(&blobs[0]).unwrap(), b"same");
+    }
+
+    #[test]
+    fn lists_deleted_files() {
+        let test = TestRepository::new("deleted");
+        let added = test.commit(&[("secret.png", b"secret")]);
+        test.commit(&[("readme.md", b"# Readme")]);
+
+        let history = GitHistory::open(&test.directory).unwrap();
+        let blobs = history.blobs().unwrap();
+
+        let secret = blobs.iter().find(|blob| blob.path == "secret.png").unwrap();
+        assert_eq!(secret.commit, added.to_string());
+        assert_eq!(history.read(secret).unwrap(), b"secret");
+    }
+
+    #[test]
+    fn reports_corrupt_blobs() {
+        let test = TestRepository::new("corrupt");
+        test.commit(&[("a.png", b"image")]);
+
+        // Replace the loose object with data that does not inflate
+        let history = GitHistory::open(&test.directory).unwrap();
+        let blobs = history.blobs().unwrap();
+        let id = blobs[0].id.to_string();
+        let object = test
+            .directory
+            .join(".git/objects")
+            .join(&id[..2])
+            .join(&id[2..]);
+        std::fs::remove_file(&object).unwrap();
+        std::fs::write(&object, b"corrupt").unwrap();
+
+        let error = history.read(&blobs[0]).unwrap_err();
+        assert!(
+            matches!(&error, CredentialDetectorError::GitError(e) if e.starts_with("a.png: ")),
+            "{error}"
+        );
+    }
+}
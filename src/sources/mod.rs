@@ -0,0 +1,107 @@
+//! Input sources other than single images
+//!
+//! Each source turns a container format into images and text lines, together with
+//! the context (page, frame, ...) recorded on the detections found in them.
+
+#[cfg(feature = "archive")]
+pub(crate) mod archive;
+pub(crate) mod document;
+#[cfg(feature = "email")]
+pub(crate) mod email;
+pub(crate) mod frames;
+#[cfg(feature = "git")]
+pub(crate) mod git;
+#[cfg(feature = "pdf")]
+pub(crate) mod pdf;
+#[cfg(feature = "video")]
+pub(crate) mod video;
+
+#[cfg(feature = "archive")]
+pub use archive::ArchiveLimits;
+pub use frames::FrameSampling;
+
+/// Unpack samples of `bits` bits (rows padded to whole bytes) into one byte each
+///
+/// Rows missing from `data` are not produced, so the result may be shorter than
+/// `row_samples * rows`.
+pub(crate) fn unpack_samples(data: &[u8], bits: usize, row_samples: usize, rows: usize) -> Vec<u8> {
+    if bits == 8 {
+        return data.to_vec();
+    }
+    if bits == 16 {
+        return data.chunks(2).map(|sample| sample[0]).collect();
+    }
+
+    let row_bytes = row_samples.saturating_mul(bits).div_ceil(8).max(1);
+    let mask = (1u16 << bits) - 1;
+    // The sizes come from the file, so only reserve what the data can supply
+    let capacity = row_samples.saturating_mul(rows).min(data.len() * 8 / bits);
+    let mut samples = Vec::with_capacity(capacity);
+    for row in data.chunks(row_bytes).take(rows) {
+        for i in 0..row_samples.min(row.len() * 8 / bits) {
+            let bit = i * bits;
+            let byte = row.get(bit / 8).copied().unwrap_or(0) as u16;
+            samples.push(((byte >> (8 - bits - bit % 8)) & mask) as u8);
+        }
+    }
+    samples
+}
+
+/// Convert 8-bit CMYK samples to RGB
+pub(crate) fn cmyk_to_rgb(samples: &[u8]) -> Vec<u8> {
+    samples
+        .chunks_exact(4)
+        .flat_map(|cmyk| {
+            let k = 255 - cmyk[3] as u16;
+            [0, 1, 2].map(|i| ((255 - cmyk[i] as u16) * k / 255) as u8)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unpacks_sub_byte_samples() {
+        // 1-bit rows of 10 samples are padded to 2 bytes
+        let data = [0b1010_0000, 0b1100_0000, 0b0000_0001, 0b0100_0000];
+        assert_eq!(
+            unpack_samples(&data, 1, 10, 2),
+            [1, 0, 1, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 1]
+        );
+
+        assert_eq!(unpack_samples(&[0b0001_1011], 2, 4, 1), [0, 1, 2, 3]);
+        assert_eq!(unpack_samples(&[0xAB, 0xC0], 4, 3, 1), [0xA, 0xB, 0xC]);
+    }
+
+    #[test]
+    fn passes_through_wide_samples() {
+        assert_eq!(unpack_samples(&[1, 2, 3], 8, 3, 1), [1, 2, 3]);
+        // 16-bit samples keep their most significant byte
+        assert_eq!(
+            unpack_samples(&[0x12, 0x34, 0xAB, 0xCD], 16, 2, 1),
+            [0x12, 0xAB]
+        );
+    }
+
+    #[test]
+    fn stops_at_the_end_of_the_data() {
+        // Rows beyond the data are not produced, whatever size the file declares
+        let samples = unpack_samples(&[0xFF], 1, 8, usize::MAX);
+        assert_eq!(samples, [1; 8]);
+
+        let samples = unpack_samples(&[0xFF], 1, usize::MAX / 2, 3);
+        assert_eq!(samples, [1; 8]);
+
+        assert!(unpack_samples(&[], 4, usize::MAX, usize::MAX).is_empty());
+    }
+
+    #[test]
+    fn converts_cmyk() {
+        assert_eq!(
+            cmyk_to_rgb(&[0, 0, 0, 0, 255, 0, 0, 0, 0, 0, 0, 255]),
+            [255, 255, 255, 0, 255, 255, 0, 0, 0]
+        );
+    }
+}
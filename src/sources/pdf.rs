@@ -0,0 +1,970 @@
+//! PDF input
+//!
+//! Reads the native text layer and the embedded raster images of each page. Page
+//! content streams are interpreted to find where text is drawn and where images are
+//! placed, so that detections can be reported in page coordinates (points, origin at
+//! the top left of the media box). Pages are not rasterised: text drawn as vector
+//! outlines is not covered.
+
+use crate::error::CredentialDetectorError;
+use crate::ocr::TextLine;
+use crate::sources::{cmyk_to_rgb, unpack_samples};
+use crate::types::BoundingBox;
+use image::{DynamicImage, GrayImage, RgbImage};
+use lopdf::content::Content;
+use lopdf::{Dictionary, Document, Encoding, Object, ObjectId, Stream};
+use std::collections::{HashSet, btree_map};
+
+/// Limit on the decompressed size of a content stream or an image
+const MAX_DECOMPRESSED_SIZE: usize = 256 << 20;
+
+/// Limit on the total size of the images decoded from one document
+const MAX_DOCUMENT_IMAGE_SIZE: usize = 1 << 30;
+
+/// Limit on the nesting of form XObjects
+const MAX_FORM_DEPTH: usize = 8;
+
+/// Limit on the form XObjects drawn on one page, which guards against forms drawing
+/// other forms many times at each level
+const MAX_FORM_DRAWS: usize = 1000;
+
+/// Limit on the content stream operations interpreted for one page
+const MAX_OPERATIONS: usize = 1_000_000;
+
+/// Limit on the depth of the page tree, which guards against reference cycles
+const MAX_TREE_DEPTH: usize = 32;
+
+/// Images smaller than this (icons, bullets, rules) cannot contain readable text
+const MIN_IMAGE_SIZE: u32 = 16;
+
+/// Average glyph width as a fraction of the font size, as glyph metrics are not read
+const GLYPH_WIDTH: f32 = 0.5;
+
+/// Affine transformation matrix `[a b c d e f]` as used by PDF
+type Matrix = [f32; 6];
+
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+/// Product of two matrices, `first` being applied first
+fn multiply(first: &Matrix, second: &Matrix) -> Matrix {
+    let [a1, b1, c1, d1, e1, f1] = *first;
+    let [a2, b2, c2, d2, e2, f2] = *second;
+    [
+        a1 * a2 + b1 * c2,
+        a1 * b2 + b1 * d2,
+        c1 * a2 + d1 * c2,
+        c1 * b2 + d1 * d2,
+        e1 * a2 + f1 * c2 + e2,
+        e1 * b2 + f1 * d2 + f2,
+    ]
+}
+
+/// Apply a matrix to a point
+fn apply(matrix: &Matrix, (x, y): (f32, f32)) -> (f32, f32) {
+    let [a, b, c, d, e, f] = *matrix;
+    (a * x + c * y + e, b * x + d * y + f)
+}
+
+/// Matrix translating by `(x, y)`
+fn translation(x: f32, y: f32) -> Matrix {
+    [1.0, 0.0, 0.0, 1.0, x, y]
+}
+
+/// Position of an embedded image on its page
+#[derive(Debug, Clone)]
+pub(crate) struct Placement {
+    /// Transformation from the image's unit square to page space
+    matrix: Matrix,
+    /// Media box of the page `[x0 y0 x1 y1]`
+    media_box: [f32; 4],
+    /// Size of the image in pixels
+    width: u32,
+    height: u32,
+}
+
+impl Placement {
+    /// Map a box in image pixels to page coordinates
+    pub fn map_box(&self, bounding_box: &BoundingBox) -> BoundingBox {
+        let corners = [
+            (bounding_box.x, bounding_box.y),
+            (bounding_box.x + bounding_box.width, bounding_box.y),
+            (bounding_box.x, bounding_box.y + bounding_box.height),
+            (
+                bounding_box.x + bounding_box.width,
+                bounding_box.y + bounding_box.height,
+            ),
+        ]
+        .map(|(x, y)| {
+            // Image space has its origin at the bottom left, pixels at the top left
+            let unit = (x / self.width as f32, 1.0 - y / self.height as f32);
+            let (page_x, page_y) = apply(&self.matrix, unit);
+            (page_x - self.media_box[0], self.media_box[3] - page_y)
+        });
+
+        let min_x = corners.iter().map(|p| p.0).fold(f32::INFINITY, f32::min);
+        let min_y = corners.iter().map(|p| p.1).fold(f32::INFINITY, f32::min);
+        let max_x = corners
+            .iter()
+            .map(|p| p.0)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let max_y = corners
+            .iter()
+            .map(|p| p.1)
+            .fold(f32::NEG_INFINITY, f32::max);
+
+        BoundingBox {
+            x: min_x,
+            y: min_y,
+            width: max_x - min_x,
+            height: max_y - min_y,
+        }
+    }
+}
+
+/// Raster image embedded in a page
+pub(crate) struct PlacedImage {
+    pub image: DynamicImage,
+    pub placement: Placement,
+}
+
+/// Text and images of a PDF page
+pub(crate) struct PdfPage {
+    /// Page number, starting at 1
+    pub number: u32,
+    /// Lines of the native text layer, in page coordinates
+    pub text_lines: Vec<TextLine>,
+    /// Embedded raster images
+    pub images: Vec<PlacedImage>,
+}
+
+/// Text drawn by one text-showing operator
+struct TextRun {
+    text: String,
+    /// Start of the baseline in page space
+    x: f32,
+    baseline: f32,
+    /// Font size in page space
+    size: f32,
+    /// Estimated width in page space
+    width: f32,
+}
+
+/// State of the content stream interpreter
+struct Interpreter<'a> {
+    document: &'a Document,
+    /// Resource dictionaries in scope, innermost last
+    resources: Vec<&'a Dictionary>,
+    ctm: Matrix,
+    saved_ctm: Vec<Matrix>,
+    text_matrix: Matrix,
+    line_matrix: Matrix,
+    font_size: f32,
+    leading: f32,
+    encoding: Option<Encoding<'a>>,
+    runs: Vec<TextRun>,
+    /// Image XObjects in drawing order with their transformation
+    images: Vec<(ObjectId, Matrix)>,
+    seen_images: HashSet<ObjectId>,
+    /// Form XObjects being drawn, outermost first
+    form_stack: Vec<ObjectId>,
+    form_draws: usize,
+    operations: usize,
+}
+
+impl<'a> Interpreter<'a> {
+    fn new(document: &'a Document, resources: Vec<&'a Dictionary>) -> Self {
+        Self {
+            document,
+            resources,
+            ctm: IDENTITY,
+            saved_ctm: Vec::new(),
+            text_matrix: IDENTITY,
+            line_matrix: IDENTITY,
+            font_size: 0.0,
+            leading: 0.0,
+            encoding: None,
+            runs: Vec::new(),
+            images: Vec::new(),
+            seen_images: HashSet::new(),
+            form_stack: Vec::new(),
+            form_draws: 0,
+            operations: 0,
+        }
+    }
+
+    /// Find a named resource (font, XObject) in the resource dictionaries in scope
+    fn resource(&self, category: &[u8], name: &[u8]) -> Option<(Option<ObjectId>, &'a Object)> {
+        self.resources.iter().rev().find_map(|resources| {
+            let (_, entries) = self
+                .document
+                .dereference(resources.get(category).ok()?)
+                .ok()?;
+            self.document
+                .dereference(entries.as_dict().ok()?.get(name).ok()?)
+                .ok()
+        })
+    }
+
+    /// Move to the start of the next line, offset by `(x, y)` in text space
+    fn next_line(&mut self, x: f32, y: f32) {
+        self.line_matrix = multiply(&translation(x, y), &self.line_matrix);
+        self.text_matrix = self.line_matrix;
+    }
+
+    /// Show text given as string operands and kerning adjustments (in 1/1000 em)
+    fn show_text(&mut self, items: &[Object]) {
+        let mut text = String::new();
+        let mut advance = 0.0;
+
+        for item in items {
+            match item {
+                Object::String(bytes, _) => {
+                    let decoded = match &self.encoding {
+                        Some(encoding) => Document::decode_text(encoding, bytes).ok(),
+                        None => None,
+                    }
+                    .unwrap_or_else(|| String::from_utf8_lossy(bytes).into_owned());
+                    advance += decoded.chars().count() as f32 * GLYPH_WIDTH * self.font_size;
+                    text.push_str(&decoded);
+                }
+                Object::Integer(_) | Object::Real(_) => {
+                    let adjustment = item.as_float().unwrap_or(0.0);
+                    advance -= adjustment / 1000.0 * self.font_size;
+                    // Large negative adjustments are used instead of space characters
+                    if adjustment < -200.0 && !text.ends_with(' ') {
+                        text.push(' ');
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let matrix = multiply(&self.text_matrix, &self.ctm);
+        let (x, baseline) = apply(&matrix, (0.0, 0.0));
+        let vertical_scale = (matrix[2] * matrix[2] + matrix[3] * matrix[3]).sqrt();
+        let horizontal_scale = (matrix[0] * matrix[0] + matrix[1] * matrix[1]).sqrt();
+
+        if !text.trim().is_empty() {
+            self.runs.push(TextRun {
+                text,
+                x,
+                baseline,
+                size: self.font_size * vertical_scale,
+                width: advance * horizontal_scale,
+            });
+        }
+
+        self.text_matrix = multiply(&translation(advance, 0.0), &self.text_matrix);
+    }
+
+    /// Draw an XObject: record images and interpret forms
+    fn draw_xobject(&mut self, name: &[u8], depth: usize) {
+        let Some((id, object)) = self.resource(b"XObject", name) else {
+            return;
+        };
+        let Ok(stream) = object.as_stream() else {
+            return;
+        };
+
+        match stream.dict.get(b"Subtype").and_then(Object::as_name) {
+            Ok(b"Image") => {
+                if let Some(id) = id
+                    && self.seen_images.insert(id)
+                {
+                    self.images.push((id, self.ctm));
+                }
+            }
+            Ok(b"Form") if depth < MAX_FORM_DEPTH && self.form_draws < MAX_FORM_DRAWS => {
+                // A form that draws itself would only repeat what is already drawn
+                if id.is_some_and(|id| self.form_stack.contains(&id)) {
+                    return;
+                }
+                self.form_draws += 1;
+
+                let Ok(content) = stream.decompressed_content_with_limit(MAX_DECOMPRESSED_SIZE)
+                else {
+                    return;
+                };
+                let form_matrix = stream
+                    .dict
+                    .get(b"Matrix")
+                    .and_then(Object::as_array)
+                    .ok()
+                    .and_then(|values| numbers(values).try_into().ok())
+                    .unwrap_or(IDENTITY);
+
+                let saved_ctm = self.ctm;
+                self.ctm = multiply(&form_matrix, &self.ctm);
+                let form_resources = stream
+                    .dict
+                    .get(b"Resources")
+                    .and_then(|resources| self.document.dereference(resources))
+                    .and_then(|(_, resources)| resources.as_dict());
+                self.form_stack.extend(id);
+                if let Ok(form_resources) = form_resources {
+                    self.resources.push(form_resources);
+                    self.run(&content, depth + 1);
+                    self.resources.pop();
+                } else {
+                    self.run(&content, depth + 1);
+                }
+                if id.is_some() {
+                    self.form_stack.pop();
+                }
+                self.ctm = saved_ctm;
+            }
+            _ => {}
+        }
+    }
+
+    /// Interpret a content stream
+    fn run(&mut self, content: &[u8], depth: usize) {
+        let Ok(content) = Content::decode(content) else {
+            return;
+        };
+
+        for operation in &content.operations {
+            if self.operations == MAX_OPERATIONS {
+                return;
+            }
+            self.operations += 1;
+
+            let operands = &operation.operands;
+            let number = |index: usize| {
+                operands
+                    .get(index)
+                    .and_then(|operand| operand.as_float().ok())
+                    .unwrap_or(0.0)
+            };
+
+            match operation.operator.as_str() {
+                "q" => self.saved_ctm.push(self.ctm),
+                "Q" => self.ctm = self.saved_ctm.pop().unwrap_or(IDENTITY),
+                "cm" => {
+                    if let Ok(matrix) = numbers(operands).try_into() {
+                        self.ctm = multiply(&matrix, &self.ctm);
+                    }
+                }
+                "BT" => {
+                    self.text_matrix = IDENTITY;
+                    self.line_matrix = IDENTITY;
+                }
+                "Tf" => {
+                    self.font_size = number(1);
+                    self.encoding = operands
+                        .first()
+                        .and_then(|name| name.as_name().ok())
+                        .and_then(|name| self.resource(b"Font", name))
+                        .and_then(|(_, font)| font.as_dict().ok())
+                        .and_then(|font| font.get_font_encoding(self.document).ok());
+                }
+                "TL" => self.leading = number(0),
+                "Td" => self.next_line(number(0), number(1)),
+                "TD" => {
+                    self.leading = -number(1);
+                    self.next_line(number(0), number(1));
+                }
+                "Tm" => {
+                    if let Ok(matrix) = numbers(operands).try_into() {
+                        self.text_matrix = matrix;
+                        self.line_matrix = matrix;
+                    }
+                }
+                "T*" => self.next_line(0.0, -self.leading),
+                "Tj" => self.show_text(operands),
+                "TJ" => {
+                    if let Some(Ok(items)) = operands.first().map(Object::as_array) {
+                        self.show_text(items);
+                    }
+                }
+                "'" => {
+                    self.next_line(0.0, -self.leading);
+                    self.show_text(operands);
+                }
+                "\"" => {
+                    self.next_line(0.0, -self.leading);
+                    self.show_text(operands.get(2..).unwrap_or_default());
+                }
+                "Do" => {
+                    if let Some(Ok(name)) = operands.first().map(Object::as_name) {
+                        self.draw_xobject(name, depth);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Numeric values of a list of operands
+fn numbers(objects: &[Object]) -> Vec<f32> {
+    objects
+        .iter()
+        .filter_map(|object| object.as_float().ok())
+        .collect()
+}
+
+/// Group text runs drawn on the same baseline into lines in page coordinates
+fn group_lines(mut runs: Vec<TextRun>, media_box: &[f32; 4]) -> Vec<TextLine> {
+    runs.sort_by(|a, b| b.baseline.total_cmp(&a.baseline).then(a.x.total_cmp(&b.x)));
+
+    let mut lines: Vec<Vec<TextRun>> = Vec::new();
+    for run in runs {
+        match lines.last_mut() {
+            Some(line) if (line[0].baseline - run.baseline).abs() < 0.3 * run.size.max(1.0) => {
+                line.push(run)
+            }
+            _ => lines.push(vec![run]),
+        }
+    }
+
+    lines
+        .into_iter()
+        .map(|mut runs| {
+            runs.sort_by(|a, b| a.x.total_cmp(&b.x));
+
+            let mut text = String::new();
+            let mut end = f32::NEG_INFINITY;
+            for run in &runs {
+                if !text.is_empty() && run.x - end > 0.3 * run.size {
+                    text.push(' ');
+                }
+                text.push_str(&run.text);
+                end = run.x + run.width;
+            }
+
+            let size = runs.iter().map(|run| run.size).fold(0.0, f32::max);
+            let x = runs[0].x;
+            let top = runs[0].baseline + 0.8 * size;
+
+            TextLine {
+                text: text.trim().to_string(),
+                x: x - media_box[0],
+                y: media_box[3] - top,
+                width: end - x,
+                height: size,
+                score: 1.0,
+            }
+        })
+        .collect()
+}
+
+/// Number of colour components and palette of an image colour space
+enum ColorSpace {
+    Gray,
+    Rgb,
+    Cmyk,
+    /// Palette of `base` colours (`Gray` or `Rgb`)
+    Indexed(Box<ColorSpace>, Vec<u8>),
+}
+
+impl ColorSpace {
+    /// Read a colour space; `depth` is the number of enclosing `Indexed` colour spaces
+    fn from_object(document: &Document, object: &Object, depth: usize) -> Option<Self> {
+        let (_, object) = document.dereference(object).ok()?;
+        match object {
+            Object::Name(name) => match name.as_slice() {
+                b"DeviceGray" | b"CalGray" | b"G" => Some(ColorSpace::Gray),
+                b"DeviceRGB" | b"CalRGB" | b"RGB" => Some(ColorSpace::Rgb),
+                b"DeviceCMYK" | b"CMYK" => Some(ColorSpace::Cmyk),
+                _ => None,
+            },
+            Object::Array(values) => match values.first()?.as_name().ok()? {
+                b"ICCBased" => {
+                    let (_, profile) = document.dereference(values.get(1)?).ok()?;
+                    match profile
+                        .as_stream()
+                        .ok()?
+                        .dict
+                        .get(b"N")
+                        .ok()?
+                        .as_i64()
+                        .ok()?
+                    {
+                        1 => Some(ColorSpace::Gray),
+                        3 => Some(ColorSpace::Rgb),
+                        4 => Some(ColorSpace::Cmyk),
+                        _ => None,
+                    }
+                }
+                // The base of an indexed colour space cannot be indexed itself, which also
+                // stops colour spaces that refer to themselves
+                b"Indexed" | b"I" if depth == 0 => {
+                    let base = ColorSpace::from_object(document, values.get(1)?, depth + 1)?;
+                    let (_, lookup) = document.dereference(values.get(3)?).ok()?;
+                    let palette = match lookup {
+                        Object::String(bytes, _) => bytes.clone(),
+                        Object::Stream(stream) => stream
+                            .decompressed_content_with_limit(MAX_DECOMPRESSED_SIZE)
+                            .unwrap_or_else(|_| stream.content.clone()),
+                        _ => return None,
+                    };
+                    Some(ColorSpace::Indexed(Box::new(base), palette))
+                }
+                b"CalGray" => Some(ColorSpace::Gray),
+                b"CalRGB" => Some(ColorSpace::Rgb),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn components(&self) -> usize {
+        match self {
+            ColorSpace::Gray | ColorSpace::Indexed(..) => 1,
+            ColorSpace::Rgb => 3,
+            ColorSpace::Cmyk => 4,
+        }
+    }
+}
+
+/// Decode an image XObject into pixels
+fn decode_image(document: &Document, stream: &Stream) -> Option<DynamicImage> {
+    let dict = &stream.dict;
+    // Negative or oversized dimensions are rejected rather than wrapped
+    let width = u32::try_from(dict.get(b"Width").ok()?.as_i64().ok()?).ok()?;
+    let height = u32::try_from(dict.get(b"Height").ok()?.as_i64().ok()?).ok()?;
+    if width < MIN_IMAGE_SIZE || height < MIN_IMAGE_SIZE {
+        return None;
+    }
+
+    let filters: Vec<&[u8]> = match dict.get(b"Filter").ok() {
+        Some(Object::Name(name)) => vec![name.as_slice()],
+        Some(Object::Array(names)) => names.iter().filter_map(|n| n.as_name().ok()).collect(),
+        _ => Vec::new(),
+    };
+
+    match filters.last().copied() {
+        // JPEG data is a complete image file
+        Some(b"DCTDecode") if filters.len() == 1 => {
+            return image::load_from_memory(&stream.content).ok();
+        }
+        // JPEG 2000, fax and JBIG2 images are not supported by the image crate
+        Some(b"DCTDecode" | b"JPXDecode" | b"CCITTFaxDecode" | b"JBIG2Decode") => return None,
+        _ => {}
+    }
+
+    let data = if filters.is_empty() {
+        stream.content.clone()
+    } else {
+        stream
+            .decompressed_content_with_limit(MAX_DECOMPRESSED_SIZE)
+            .ok()?
+    };
+
+    let image_mask = dict
+        .get(b"ImageMask")
+        .and_then(Object::as_bool)
+        .unwrap_or(false);
+    let (color_space, bits) = if image_mask {
+        (ColorSpace::Gray, 1)
+    } else {
+        let color_space = ColorSpace::from_object(document, dict.get(b"ColorSpace").ok()?, 0)?;
+        let bits = dict
+            .get(b"BitsPerComponent")
+            .and_then(Object::as_i64)
+            .unwrap_or(8) as usize;
+        (color_space, bits)
+    };
+    if !matches!(bits, 1 | 2 | 4 | 8 | 16) {
+        return None;
+    }
+
+    // The declared size must fit the data, so that a small file cannot make the buffers
+    // below huge
+    let row_samples = (width as usize).checked_mul(color_space.components())?;
+    let row_bytes = row_samples.checked_mul(bits)?.div_ceil(8);
+    if row_bytes.checked_mul(height as usize)? > data.len() {
+        return None;
+    }
+
+    let mut samples = unpack_samples(&data, bits, row_samples, height as usize);
+    samples.truncate(row_samples * height as usize);
+
+    // Scale samples of less than 8 bits to the full range (palette indices excepted)
+    if bits < 8 && !matches!(color_space, ColorSpace::Indexed(..)) {
+        let max = (1u16 << bits) - 1;
+        for sample in &mut samples {
+            *sample = (*sample as u16 * 255 / max) as u8;
+        }
+    }
+
+    match color_space {
+        ColorSpace::Gray => {
+            GrayImage::from_raw(width, height, samples).map(DynamicImage::ImageLuma8)
+        }
+        ColorSpace::Rgb => RgbImage::from_raw(width, height, samples).map(DynamicImage::ImageRgb8),
+        ColorSpace::Cmyk => {
+            RgbImage::from_raw(width, height, cmyk_to_rgb(&samples)).map(DynamicImage::ImageRgb8)
+        }
+        ColorSpace::Indexed(base, palette) => {
+            let base_components = base.components();
+            let rgb = samples
+                .iter()
+                .flat_map(|&index| {
+                    let start = index as usize * base_components;
+                    let color = palette
+                        .get(start..start + base_components)
+                        .unwrap_or(&[0, 0, 0][..base_components]);
+                    match color {
+                        [gray] => [*gray; 3],
+                        [r, g, b] => [*r, *g, *b],
+                        _ => [0; 3],
+                    }
+                })
+                .collect();
+            RgbImage::from_raw(width, height, rgb).map(DynamicImage::ImageRgb8)
+        }
+    }
+}
+
+/// Media box of a page, which may be inherited from the page tree
+fn media_box(document: &Document, page: &Dictionary) -> Option<[f32; 4]> {
+    let mut node = page;
+    for _ in 0..MAX_TREE_DEPTH {
+        if let Ok(values) = node.get(b"MediaBox")
+            && let Ok((_, values)) = document.dereference(values)
+            && let Ok(values) = values.as_array()
+        {
+            return numbers(values).try_into().ok();
+        }
+        node = document
+            .dereference(node.get(b"Parent").ok()?)
+            .ok()?
+            .1
+            .as_dict()
+            .ok()?;
+    }
+    None
+}
+
+/// Pages of a PDF document, read one at a time
+pub(crate) struct PdfPages {
+    document: Document,
+    page_ids: btree_map::IntoIter<u32, ObjectId>,
+    /// Image bytes that may still be decoded; later images are skipped
+    image_budget: usize,
+}
+
+impl PdfPages {
+    /// Read the text layer and embedded images of a page
+    fn read_page(
+        &mut self,
+        number: u32,
+        page_id: ObjectId,
+    ) -> Result<PdfPage, CredentialDetectorError> {
+        let document = &self.document;
+        let page = document
+            .get_dictionary(page_id)
+            .map_err(|e| CredentialDetectorError::PdfLoadError(e.to_string()))?;
+        let media_box = media_box(document, page).unwrap_or([0.0, 0.0, 612.0, 792.0]);
+
+        // Resources may be inherited from the page tree; the page's own come last
+        let (own_resources, inherited) = match document.get_page_resources(page_id) {
+            Ok(resources) => resources,
+            Err(e) => return Err(CredentialDetectorError::PdfLoadError(e.to_string())),
+        };
+        let mut resources: Vec<&Dictionary> = inherited
+            .iter()
+            .rev()
+            .filter_map(|&id| document.get_dictionary(id).ok())
+            .collect();
+        resources.extend(own_resources);
+
+        let content = match document.get_page_content_with_limit(page_id, MAX_DECOMPRESSED_SIZE) {
+            Ok(content) => content,
+            Err(e) => return Err(CredentialDetectorError::PdfLoadError(e.to_string())),
+        };
+
+        let mut interpreter = Interpreter::new(document, resources);
+        interpreter.run(&content, 0);
+
+        let mut images = Vec::new();
+        for &(id, matrix) in &interpreter.images {
+            if self.image_budget == 0 {
+                break;
+            }
+            let Some(image) = document
+                .get_object(id)
+                .and_then(Object::as_stream)
+                .ok()
+                .and_then(|stream| decode_image(document, stream))
+            else {
+                continue;
+            };
+            self.image_budget = self.image_budget.saturating_sub(image.as_bytes().len());
+            images.push(PlacedImage {
+                placement: Placement {
+                    matrix,
+                    media_box,
+                    width: image.width(),
+                    height: image.height(),
+                },
+                image,
+            });
+        }
+
+        Ok(PdfPage {
+            number,
+            text_lines: group_lines(interpreter.runs, &media_box),
+            images,
+        })
+    }
+}
+
+impl Iterator for PdfPages {
+    type Item = Result<PdfPage, CredentialDetectorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Page tree entries that are not dictionaries are skipped
+        let (number, page_id) = self
+            .page_ids
+            .find(|&(_, page_id)| self.document.get_dictionary(page_id).is_ok())?;
+        Some(self.read_page(number, page_id))
+    }
+}
+
+/// Read the text layer and embedded images of the pages of a PDF document
+///
+/// Pages are read as the iterator advances, so only one page's images are held at a
+/// time. Images past [`MAX_DOCUMENT_IMAGE_SIZE`] decoded bytes are skipped.
+pub(crate) fn read_pdf(pdf_data: &[u8]) -> Result<PdfPages, CredentialDetectorError> {
+    let document = match Document::load_mem(pdf_data) {
+        Ok(document) => document,
+        Err(e) => return Err(CredentialDetectorError::PdfLoadError(e.to_string())),
+    };
+
+    Ok(PdfPages {
+        page_ids: document.get_pages().into_iter(),
+        document,
+        image_budget: MAX_DOCUMENT_IMAGE_SIZE,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::dictionary;
+
+    const TEXT: &[u8] = b"BT /F1 12 Tf 72 700 Td (password: hunter2) Tj ET";
+
+    /// Save a one-page PDF with the given content stream and XObjects
+    ///
+    /// `xobjects` is called with the document to add its objects to.
+    fn pdf_with_page(
+        content: &[u8],
+        xobjects: impl FnOnce(&mut Document) -> Dictionary,
+    ) -> Vec<u8> {
+        let mut document = Document::with_version("1.5");
+        let pages_id = document.new_object_id();
+
+        let font_id = document.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+        });
+        let xobjects = xobjects(&mut document);
+        let content_id = document.add_object(Stream::new(dictionary! {}, content.to_vec()));
+        let page_id = document.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+            "Resources" => dictionary! {
+                "Font" => dictionary! { "F1" => font_id },
+                "XObject" => xobjects,
+            },
+        });
+        document.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page_id.into()],
+                "Count" => 1,
+                "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+            }),
+        );
+        let catalog_id = document.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        document.trailer.set("Root", catalog_id);
+
+        let mut pdf = Vec::new();
+        document.save_to(&mut pdf).unwrap();
+        pdf
+    }
+
+    /// Image XObject with the given colour space
+    fn image(width: i64, height: i64, bits: i64, color_space: Object, data: Vec<u8>) -> Stream {
+        Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => width,
+                "Height" => height,
+                "ColorSpace" => color_space,
+                "BitsPerComponent" => bits,
+            },
+            data,
+        )
+    }
+
+    /// Build a one-page PDF drawing a gray image XObject and a line of text
+    fn pdf_with_image(width: i64, height: i64, bits: i64, data: Vec<u8>) -> Vec<u8> {
+        let content = [TEXT, b" q 100 0 0 100 72 500 cm /Im1 Do Q"].concat();
+        pdf_with_page(&content, |document| {
+            let image_id =
+                document.add_object(image(width, height, bits, "DeviceGray".into(), data));
+            dictionary! { "Im1" => image_id }
+        })
+    }
+
+    fn read_pages(pdf: &[u8]) -> Vec<PdfPage> {
+        read_pdf(pdf).unwrap().collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn reads_text_and_images() {
+        let pages = read_pages(&pdf_with_image(20, 20, 8, vec![128; 400]));
+
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].number, 1);
+        assert_eq!(pages[0].text_lines.len(), 1);
+        assert_eq!(pages[0].text_lines[0].text, "password: hunter2");
+        assert_eq!(pages[0].images.len(), 1);
+        assert_eq!(pages[0].images[0].image.width(), 20);
+    }
+
+    #[test]
+    fn skips_malformed_image_dictionaries() {
+        for (width, height) in [
+            // Negative sizes used to wrap around to huge ones
+            (-1, 20),
+            (20, -20),
+            // Sizes far larger than the data
+            (100_000, 100_000),
+            (i64::from(u32::MAX), i64::from(u32::MAX)),
+            (i64::MAX, 1),
+            // Slightly too little data
+            (20, 21),
+        ] {
+            for bits in [1, 8] {
+                let pdf = pdf_with_image(width, height, bits, vec![128; 400 * bits as usize / 8]);
+                let pages = read_pages(&pdf);
+
+                assert!(pages[0].images.is_empty(), "{width}x{height}x{bits}");
+                assert_eq!(pages[0].text_lines.len(), 1, "{width}x{height}x{bits}");
+            }
+        }
+    }
+
+    #[test]
+    fn reads_indexed_images() {
+        let content = b"q 100 0 0 100 72 500 cm /Im1 Do Q";
+        let pdf = pdf_with_page(content, |document| {
+            let palette = Object::string_literal(vec![0, 0, 0, 255, 255, 255]);
+            let color_space = vec!["Indexed".into(), "DeviceRGB".into(), 1.into(), palette];
+            let image_id = document.add_object(image(20, 20, 8, color_space.into(), vec![1; 400]));
+            dictionary! { "Im1" => image_id }
+        });
+        let pages = read_pages(&pdf);
+
+        assert_eq!(pages[0].images.len(), 1);
+        assert_eq!(
+            pages[0].images[0].image.to_rgb8().get_pixel(0, 0).0,
+            [255; 3]
+        );
+    }
+
+    #[test]
+    fn rejects_self_referencing_indexed_color_spaces() {
+        let pdf = pdf_with_page(&[TEXT, b" /Im1 Do"].concat(), |document| {
+            // The base of the colour space is the colour space itself
+            let color_space_id = document.new_object_id();
+            let palette = Object::string_literal(vec![0, 255]);
+            document.objects.insert(
+                color_space_id,
+                Object::Array(vec![
+                    "Indexed".into(),
+                    color_space_id.into(),
+                    1.into(),
+                    palette,
+                ]),
+            );
+            let image_id =
+                document.add_object(image(20, 20, 8, color_space_id.into(), vec![1; 400]));
+            dictionary! { "Im1" => image_id }
+        });
+        let pages = read_pages(&pdf);
+
+        assert!(pages[0].images.is_empty());
+        assert_eq!(pages[0].text_lines[0].text, "password: hunter2");
+    }
+
+    #[test]
+    fn limits_form_xobjects() {
+        // A form drawing itself, and forms drawing the next level ten times each
+        let pdf = pdf_with_page(b"/Self Do /Fan0 Do", |document| {
+            let self_id = document.new_object_id();
+            document.objects.insert(
+                self_id,
+                Object::Stream(Stream::new(
+                    dictionary! {
+                        "Subtype" => "Form",
+                        "Resources" => dictionary! {
+                            "XObject" => dictionary! { "Self" => self_id },
+                        },
+                    },
+                    [TEXT, b" /Self Do"].concat(),
+                )),
+            );
+
+            let mut next_id = document.add_object(Stream::new(
+                dictionary! { "Subtype" => "Form" },
+                TEXT.to_vec(),
+            ));
+            for _ in 0..MAX_FORM_DEPTH {
+                next_id = document.add_object(Stream::new(
+                    dictionary! {
+                        "Subtype" => "Form",
+                        "Resources" => dictionary! {
+                            "XObject" => dictionary! { "Next" => next_id },
+                        },
+                    },
+                    b"/Next Do ".repeat(10),
+                ));
+            }
+
+            dictionary! { "Self" => self_id, "Fan0" => next_id }
+        });
+
+        let pdf = read_pdf(&pdf).unwrap();
+        let document = &pdf.document;
+        let (_, page_id) = document.get_pages().into_iter().next().unwrap();
+        let (own_resources, _) = document.get_page_resources(page_id).unwrap();
+        let mut interpreter = Interpreter::new(document, own_resources.into_iter().collect());
+        interpreter.run(b"/Self Do /Fan0 Do", 0);
+
+        assert!(interpreter.form_stack.is_empty());
+        assert_eq!(interpreter.form_draws, MAX_FORM_DRAWS);
+        assert!(!interpreter.runs.is_empty());
+    }
+
+    #[test]
+    fn limits_decoded_image_size() {
+        let mut pages = read_pdf(&pdf_with_image(20, 20, 8, vec![128; 400])).unwrap();
+        pages.image_budget = 0;
+        let page = pages.next().unwrap().unwrap();
+
+        assert!(page.images.is_empty());
+        assert_eq!(page.text_lines.len(), 1);
+    }
+
+    #[test]
+    fn rejects_invalid_documents() {
+        assert!(matches!(
+            read_pdf(b"%PDF-1.5 garbage"),
+            Err(CredentialDetectorError::PdfLoadError(_))
+        ));
+    }
+}
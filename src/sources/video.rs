@@ -0,0 +1,265 @@
+//! Video input
+//!
+//! Demultiplexes MP4/MOV files and decodes their H.264 video track with the bundled
+//! OpenH264 decoder. Other codecs (HEVC, VP9, AV1) are not supported.
+
+use crate::error::CredentialDetectorError;
+use crate::sources::frames::{Frame, Frames};
+use image::{DynamicImage, RgbImage};
+use mp4::{MediaType, Mp4Reader};
+use openh264::decoder::{DecodedYUV, Decoder};
+use openh264::formats::YUVSource;
+use std::collections::VecDeque;
+use std::io::Cursor;
+use std::time::Duration;
+
+/// Annex B start code, which OpenH264 expects in front of each NAL unit
+const START_CODE: [u8; 4] = [0, 0, 0, 1];
+
+/// File type brands of MP4 and QuickTime video files
+const VIDEO_BRANDS: [&[u8; 4]; 16] = [
+    b"isom", b"iso2", b"iso3", b"iso4", b"iso5", b"iso6", b"mp41", b"mp42", b"avc1", b"qt  ",
+    b"M4V ", b"M4VH", b"M4VP", b"mmp4", b"MSNV", b"dash",
+];
+
+/// File type brands of other ISO base media files: audio (m4a), HEIF/AVIF images and
+/// 3GPP recordings, which often list a video brand as compatible
+const NON_VIDEO_BRANDS: [&[u8; 4]; 19] = [
+    b"M4A ", b"M4B ", b"M4P ", b"F4A ", b"F4B ", b"mif1", b"msf1", b"miaf", b"heic", b"heix",
+    b"heim", b"heis", b"hevc", b"hevx", b"avif", b"avis", b"3gp4", b"3gp5", b"3gp6",
+];
+
+/// Determine if data is an MP4 or MOV video, from the brands of its `ftyp` box
+pub(crate) fn is_video(data: &[u8]) -> bool {
+    if data.get(4..8) != Some(b"ftyp") {
+        return false;
+    }
+
+    // Major brand, minor version, then compatible brands up to the end of the box
+    let size = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+    let ftyp = &data[..size.clamp(8, data.len())];
+    let brands: Vec<&[u8]> = ftyp
+        .get(8..12)
+        .into_iter()
+        .chain(ftyp.get(16..).unwrap_or_default().chunks_exact(4))
+        .collect();
+
+    let is_any = |list: &[&[u8; 4]]| {
+        brands
+            .iter()
+            .any(|&brand| list.iter().any(|b| b[..] == *brand))
+    };
+    is_any(&VIDEO_BRANDS) && !is_any(&NON_VIDEO_BRANDS)
+}
+
+/// Decoded frames of the H.264 track of an MP4 file
+struct VideoFrames<'a> {
+    reader: Mp4Reader<Cursor<&'a [u8]>>,
+    decoder: Decoder,
+    track_id: u32,
+    timescale: u32,
+    /// Size of the NAL unit length prefixes in bytes
+    length_size: usize,
+    /// Parameter sets to feed the decoder before the first sample
+    parameter_sets: Option<Vec<u8>>,
+    next_sample: u32,
+    sample_count: u32,
+    /// Index of the next decoded frame
+    index: usize,
+    /// Presentation time of the last decoded sample
+    timestamp: Duration,
+    /// Frames left in the decoder after the last sample
+    remaining: Option<VecDeque<RgbImage>>,
+}
+
+impl VideoFrames<'_> {
+    /// Convert length-prefixed NAL units to Annex B
+    fn to_annex_b(&self, sample: &[u8], buffer: &mut Vec<u8>) {
+        let mut rest = sample;
+        while rest.len() > self.length_size {
+            let length = rest[..self.length_size]
+                .iter()
+                .fold(0usize, |length, &byte| (length << 8) | byte as usize);
+            let end = (self.length_size + length).min(rest.len());
+            buffer.extend_from_slice(&START_CODE);
+            buffer.extend_from_slice(&rest[self.length_size..end]);
+            rest = &rest[end..];
+        }
+    }
+
+    fn frame(&mut self, image: RgbImage) -> Frame {
+        let index = self.index;
+        self.index += 1;
+        Frame {
+            index,
+            timestamp: Some(self.timestamp),
+            image: DynamicImage::ImageRgb8(image),
+        }
+    }
+}
+
+/// Convert a decoded picture to RGB
+fn to_rgb(yuv: &DecodedYUV) -> Option<RgbImage> {
+    let (width, height) = yuv.dimensions();
+    let mut rgb = vec![0; width * height * 3];
+    yuv.write_rgb8(&mut rgb);
+    RgbImage::from_raw(width as u32, height as u32, rgb)
+}
+
+impl Iterator for VideoFrames<'_> {
+    type Item = Result<Frame, CredentialDetectorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next_sample <= self.sample_count {
+            let sample_id = self.next_sample;
+            self.next_sample += 1;
+
+            let sample = match self.reader.read_sample(self.track_id, sample_id) {
+                Ok(Some(sample)) => sample,
+                Ok(None) => continue,
+                Err(e) => return Some(Err(CredentialDetectorError::VideoLoadError(e.to_string()))),
+            };
+
+            // Decoding order and presentation order differ with B-frames, so the
+            // timestamp of the sample that completed a picture is approximate
+            let time = (sample.start_time as i64 + sample.rendering_offset as i64).max(0) as u64;
+            self.timestamp = Duration::from_secs_f64(time as f64 / self.timescale as f64);
+
+            let mut packet = self.parameter_sets.take().unwrap_or_default();
+            self.to_annex_b(&sample.bytes, &mut packet);
+
+            // Corrupt samples are skipped; the decoder resynchronises at the next keyframe
+            if let Ok(Some(yuv)) = self.decoder.decode(&packet)
+                && let Some(image) = to_rgb(&yuv)
+            {
+                return Some(Ok(self.frame(image)));
+            }
+        }
+
+        if self.remaining.is_none() {
+            let remaining = self
+                .decoder
+                .flush_remaining()
+                .map(|pictures| pictures.iter().filter_map(to_rgb).collect())
+                .unwrap_or_default();
+            self.remaining = Some(remaining);
+        }
+        let image = self.remaining.as_mut()?.pop_front()?;
+        Some(Ok(self.frame(image)))
+    }
+}
+
+/// Read the frames of the H.264 video track of an MP4 or MOV file
+///
+/// Files without an H.264 track (e.g. HEVC video or audio only) have no frames.
+pub(crate) fn read_video(video_data: &[u8]) -> Result<Frames<'_>, CredentialDetectorError> {
+    let reader = match Mp4Reader::read_header(Cursor::new(video_data), video_data.len() as u64) {
+        Ok(reader) => reader,
+        Err(e) => return Err(CredentialDetectorError::VideoLoadError(e.to_string())),
+    };
+
+    // Audio-only files and other codecs have nothing to scan
+    let Some(track) = reader
+        .tracks()
+        .values()
+        .find(|track| matches!(track.media_type(), Ok(MediaType::H264)))
+    else {
+        return Ok(Box::new(std::iter::empty()));
+    };
+
+    let Some(avc1) = &track.trak.mdia.minf.stbl.stsd.avc1 else {
+        return Err(CredentialDetectorError::VideoLoadError(
+            "No H.264 decoder configuration found".to_string(),
+        ));
+    };
+    let mut parameter_sets = Vec::new();
+    for nal_unit in avc1
+        .avcc
+        .sequence_parameter_sets
+        .iter()
+        .chain(&avc1.avcc.picture_parameter_sets)
+    {
+        parameter_sets.extend_from_slice(&START_CODE);
+        parameter_sets.extend_from_slice(&nal_unit.bytes);
+    }
+
+    let decoder = match Decoder::new() {
+        Ok(decoder) => decoder,
+        Err(e) => return Err(CredentialDetectorError::VideoLoadError(e.to_string())),
+    };
+
+    Ok(Box::new(VideoFrames {
+        track_id: track.track_id(),
+        timescale: track.timescale().max(1),
+        length_size: (avc1.avcc.length_size_minus_one & 0x3) as usize + 1,
+        parameter_sets: Some(parameter_sets),
+        next_sample: 1,
+        sample_count: track.sample_count(),
+        index: 0,
+        timestamp: Duration::ZERO,
+        remaining: None,
+        reader,
+        decoder,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn ftyp(major: &[u8; 4], compatible: &[&[u8; 4]]) -> Vec<u8> {
+        let size = 16 + 4 * compatible.len() as u32;
+        let mut data = size.to_be_bytes().to_vec();
+        data.extend_from_slice(b"ftyp");
+        data.extend_from_slice(major);
+        data.extend_from_slice(&[0; 4]);
+        compatible
+            .iter()
+            .for_each(|brand| data.extend_from_slice(*brand));
+        // Followed by another box, which must not be read as a brand
+        data.extend_from_slice(b"\0\0\0\x08heic");
+        data
+    }
+
+    #[test]
+    fn detects_video_brands() {
+        assert!(is_video(&ftyp(
+            b"isom",
+            &[b"isom", b"iso2", b"avc1", b"mp41"]
+        )));
+        assert!(is_video(&ftyp(b"qt  ", &[b"qt  "])));
+        assert!(is_video(&ftyp(b"XAVC", &[b"mp42", b"iso2"])));
+        assert!(is_video(&ftyp(b"mp42", &[])));
+    }
+
+    #[test]
+    fn ignores_images_audio_and_3gp() {
+        assert!(!is_video(&ftyp(b"heic", &[b"mif1", b"heic"])));
+        assert!(!is_video(&ftyp(
+            b"avif",
+            &[b"avif", b"mif1", b"miaf", b"MA1B"]
+        )));
+        assert!(!is_video(&ftyp(b"mif1", &[b"mif1", b"iso8"])));
+        assert!(!is_video(&ftyp(b"M4A ", &[b"M4A ", b"mp42", b"isom"])));
+        assert!(!is_video(&ftyp(b"3gp4", &[b"isom", b"3gp4"])));
+        assert!(!is_video(b"\0\0\0\x10ftyp"));
+        assert!(!is_video(b"GIF89a"));
+    }
+
+    #[test]
+    fn reads_no_frames_without_h264_track() {
+        let config = mp4::Mp4Config {
+            major_brand: str::parse("isom").unwrap(),
+            minor_version: 512,
+            compatible_brands: vec![str::parse("isom").unwrap()],
+            timescale: 1000,
+        };
+        let mut writer = mp4::Mp4Writer::write_start(Cursor::new(Vec::new()), &config).unwrap();
+        writer.write_end().unwrap();
+        let data = writer.into_writer().into_inner();
+
+        assert!(is_video(&data));
+        assert_eq!(read_video(&data).unwrap().count(), 0);
+    }
+}
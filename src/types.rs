@@ -1,5 +1,10 @@
 //! Common type definitions
 
+use std::collections::BTreeMap;
+
+use crate::detectors::CredentialDetectorLogic;
+use crate::error::CredentialDetectorError;
+
 /// Struct representing text position information
 #[derive(Debug, Clone)]
 pub struct BoundingBox {
@@ -13,6 +18,55 @@ pub struct BoundingBox {
     pub height: f32,
 }
 
+impl BoundingBox {
+    /// Smallest box containing both boxes
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = (self.x + self.width).max(other.x + other.width);
+        let bottom = (self.y + self.height).max(other.y + other.height);
+
+        BoundingBox {
+            x,
+            y,
+            width: right - x,
+            height: bottom - y,
+        }
+    }
+}
+
+/// Category of information a detector looks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DetectorCategory {
+    /// Cloud provider and service API keys
+    CloudKey,
+    /// Bearer tokens, session tokens and one-time password seeds
+    Token,
+    /// Passwords, including those embedded in connection strings
+    Password,
+    /// Cryptographic private keys and wallet recovery phrases
+    PrivateKey,
+    /// Payment card numbers
+    PaymentCard,
+    /// Personally identifiable information (email addresses, phone numbers, etc.)
+    Pii,
+    /// Secrets that do not fit a more specific category
+    GenericSecret,
+}
+
+/// Severity of a detection, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Low impact (e.g. contact information)
+    Low,
+    /// Moderate impact
+    Medium,
+    /// Grants access to systems or sensitive personal data
+    High,
+    /// Grants broad access or control over funds
+    Critical,
+}
+
 /// Struct representing credential detection result
 #[derive(Debug, Clone)]
 pub struct CredentialDetection {
@@ -26,4 +80,66 @@ pub struct CredentialDetection {
     pub credential_score: f32,
     /// Name of the detector used for detection
     pub detector_name: String,
+    /// Stable identifier of the rule that produced the detection
+    pub rule_id: String,
+    /// Human-readable description of what was detected
+    pub description: String,
+    /// Severity of the detection
+    pub severity: Severity,
+    /// Category of the detected information
+    pub category: DetectorCategory,
+    /// URL describing how to remediate the leak
+    pub remediation_url: Option<String>,
+    /// Additional detector-specific information (e.g. decoded token claims)
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl CredentialDetection {
+    /// Create a detection, taking the rule information from the detector
+    pub fn new<D: CredentialDetectorLogic + ?Sized>(
+        detector: &D,
+        text: String,
+        bounding_box: BoundingBox,
+        text_detection_score: f32,
+        credential_score: f32,
+    ) -> Self {
+        Self {
+            text,
+            bounding_box,
+            text_detection_score,
+            credential_score,
+            detector_name: detector.name().to_string(),
+            rule_id: detector.rule_id().to_string(),
+            description: detector.description().to_string(),
+            severity: detector.severity(),
+            category: detector.category(),
+            remediation_url: detector.remediation_url().map(str::to_string),
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Override the severity of the rule for this detection
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Set detector-specific information
+    pub fn with_metadata(mut self, metadata: BTreeMap<String, String>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+}
+
+/// Result of scanning a container such as an archive
+///
+/// Entries that could not be scanned are reported even when nothing was detected, so
+/// that a container whose entries were all skipped does not look clean.
+#[derive(Debug)]
+pub struct ScanReport {
+    /// Detections, sorted by score in descending order
+    pub detections: Vec<CredentialDetection>,
+    /// Errors of the entries that were skipped, such as corrupt images, entries over the
+    /// size limit and archives nested too deep
+    pub skipped: Vec<CredentialDetectorError>,
 }